@@ -1,5 +1,4 @@
 extern crate sdl2sketch;
-extern crate rand;
 
 use sdl2sketch::*;
 use sdl2sketch::utils::map;
@@ -11,36 +10,30 @@ struct Point {
 }
 
 impl Point {
-	fn next_pt(&mut self) {
+	fn next_pt(&mut self, s: &mut Sketch) {
 		
 		// point transformations for Barnsley fern
 		let trans = [
-			[ 0.00, 0.00, 0.00, 0.16, 0.00, 0.00, 0.01],
-			[ 0.85, 0.04,-0.04, 0.85, 0.00, 1.60, 0.86],
-			[ 0.20,-0.26, 0.23, 0.22, 0.00, 1.60, 0.93],
-			[-0.15, 0.28, 0.26, 0.24, 0.00, 0.44, 1.00]
+			[ 0.00, 0.00, 0.00, 0.16, 0.00, 0.00],
+			[ 0.85, 0.04,-0.04, 0.85, 0.00, 1.60],
+			[ 0.20,-0.26, 0.23, 0.22, 0.00, 1.60],
+			[-0.15, 0.28, 0.26, 0.24, 0.00, 0.44]
 		];
+		let probabilities = [0.01, 0.85, 0.07, 0.07];
 
 		// point transformations for alternative fern
 		// let trans = [
-		//	 [ 0.000, 0.000, 0.000, 0.250, 0.000,-0.400, 0.020],
-		//	 [ 0.950, 0.005,-0.005, 0.930,-0.002, 0.500, 0.860],
-		//	 [ 0.035,-0.200, 0.160, 0.040,-0.090, 0.020, 0.930],
-		//	 [-0.040, 0.200, 0.160, 0.040, 0.083, 0.120, 1.000]
+		//	 [ 0.000, 0.000, 0.000, 0.250, 0.000,-0.400],
+		//	 [ 0.950, 0.005,-0.005, 0.930,-0.002, 0.500],
+		//	 [ 0.035,-0.200, 0.160, 0.040,-0.090, 0.020],
+		//	 [-0.040, 0.200, 0.160, 0.040, 0.083, 0.120]
 		// ];
+		// let probabilities = [0.02, 0.84, 0.07, 0.07];
 
-		// choose transformation randomly based on probabilities in the last column
-		let r: f32 = rand::random();
-		let mut func: usize = 0;
-		for t in &trans {
-			if r < t[6] {
-				break;
-			}
-			func += 1;
-		}
+		// choose transformation randomly based on the probabilities
+		let t = s.random_weighted_choice(&trans, &probabilities).unwrap();
 
 		// perform transformation
-		let t = &trans[func];
 		let new_x = t[0] * self.x + t[1] * self.y + t[4];
 		let new_y = t[2] * self.x + t[3] * self.y + t[5];
 		self.x = new_x;
@@ -57,7 +50,7 @@ impl MainLoopMethods for Point {
 
 	fn draw(&mut self, s: &mut Sketch) {
 		for _i in 0..50 {
-			self.next_pt();
+			self.next_pt(s);
 			let px = map(self.x, -2.1820, 2.6558, 0.0, s.width() as f32) as i32;
 			let py = map(self.y, 0.0, 9.9983, s.height() as f32, 0.0) as i32;
 			s.point(px, py);
@@ -73,19 +73,24 @@ impl MainLoopMethods for MainState {
 		s.image(&self.img, self.pos.0, self.pos.1, 0, 0);
 	}
 
-	fn key_released(&mut self, _s: &mut Sketch, code: Keycode) {
-		match code {
+	fn key_released(&mut self, _s: &mut Sketch, key: KeyEvent) {
+		match key.keycode {
 			Keycode::A => { println!("A released"); }
+			Keycode::S if key.ctrl() => { println!("Ctrl+S released"); }
 			_ => {}
 		}
 	}
 
+	fn key_typed(&mut self, _s: &mut Sketch, text: &str) {
+		println!("typed \"{}\"", text);
+	}
+
 	/*
 	fn mouse_moved(&mut self, _s: &mut Sketch, x: i32, y: i32, xrel: i32, yrel: i32) {
 		println!("mouse_moved: ({},{}) -> ({},{})", xrel, yrel, x, y);
 	}
-	fn mouse_dragged(&mut self, _s: &mut Sketch, x: i32, y: i32, xrel: i32, yrel: i32) {
-		println!("mouse_dragged: ({},{}) -> ({},{})", xrel, yrel, x, y);
+	fn mouse_dragged(&mut self, _s: &mut Sketch, drag: &MouseDrag, x: i32, y: i32, xrel: i32, yrel: i32) {
+		println!("mouse_dragged with {:?}: ({},{}) -> ({},{}), started at ({},{})", drag.buttons, xrel, yrel, x, y, drag.start_x, drag.start_y);
 	}
 	*/
 
@@ -1,8 +1,6 @@
 extern crate sdl2sketch;
-extern crate rand;
 
 use sdl2sketch::*;
-use rand::Rng;
 
 const WIDTH: i32 = 640;
 const HEIGHT: i32 = 640;
@@ -19,7 +17,7 @@ fn index(row: i32, col: i32) -> usize {
 
 fn main() {
 	let mut s = Sketch::new(WIDTH as u32, HEIGHT as u32, "Game of Life");
-	let mut universe = Universe::new();
+	let mut universe = Universe::new(&mut s);
 	sdl2sketch::run(&mut s, &mut universe);
 }
 
@@ -29,12 +27,12 @@ struct Universe {
 }
 
 impl Universe {
-	fn new() -> Universe { // generates random start
+	fn new(s: &mut Sketch) -> Universe { // generates random start
 		let mut cells = Vec::new();
 		for i in 0..NROWS {
 			for j in 0..NCOLS {
-				let r: f32 = rand::thread_rng().gen();
-				cells.push(Cell::new(i, j, r < INIT_LIFE_PROB));
+				let alive = s.random(1.0) < INIT_LIFE_PROB;
+				cells.push(Cell::new(i, j, alive, s.random_range(0, 360)));
 			}
 		}
 
@@ -44,21 +42,27 @@ impl Universe {
 
 impl MainLoopMethods for Universe {
 	fn setup(&mut self, s: &mut Sketch) {
-		s.set_framerate(25);
+		s.set_framerate(60);
+		s.set_update_rate(25); // generations per second
 	}
 
-	fn draw(&mut self, s: &mut Sketch) {
-		s.background(Color::RGB(33, 33, 33));
-
+	fn update(&mut self, _s: &mut Sketch) {
 		let prev = self.clone();
 
 		for i in 0..NROWS {
 			for j in 0..NCOLS {
 				self.cells[index(i, j)].update(&prev);
-				self.cells[index(i, j)].draw(s);
 			}
 		}
 	}
+
+	fn draw(&mut self, s: &mut Sketch) {
+		s.background(Color::RGB(33, 33, 33));
+
+		for cell in &self.cells {
+			cell.draw(s);
+		}
+	}
 }
 
 
@@ -71,8 +75,8 @@ struct Cell {
 }
 
 impl Cell {
-	pub fn new(row: i32, col: i32, alive: bool) -> Self {
-		let rgb = utils::hsv_to_rgb(rand::thread_rng().gen_range(0, 360), 1.0, 1.0);
+	pub fn new(row: i32, col: i32, alive: bool, hue: u16) -> Self {
+		let rgb = utils::hsv_to_rgb(hue, 1.0, 1.0);
 		let color = Color::RGB(rgb.0, rgb.1, rgb.2);
 		Cell {
 			row,
@@ -0,0 +1,43 @@
+extern crate sdl2sketch;
+use sdl2sketch::*;
+use std::path::Path;
+
+struct Viewer {
+	img: Option<Image>,
+}
+
+impl MainLoopMethods for Viewer {
+	fn setup(&mut self, s: &mut Sketch) {
+		s.set_framerate(30);
+		s.resizable(true);
+	}
+
+	fn draw(&mut self, s: &mut Sketch) {
+		s.background(Color::RGB(33, 33, 33));
+		match self.img {
+			Some(ref img) => {
+				s.image_mode(ImageMode::CENTER);
+				let (cx, cy) = (s.width() / 2, s.height() / 2);
+				s.image(img, cx, cy, 0, 0);
+			},
+			None => {
+				s.no_fill();
+				s.stroke(Color::RGB(160, 160, 160));
+				s.rect(20, 20, (s.width() - 40) as u32, (s.height() - 40) as u32);
+			}
+		}
+	}
+
+	fn file_dropped(&mut self, s: &mut Sketch, path: &Path) {
+		match s.try_load_image(path) {
+			Ok(img) => { self.img = Some(img); },
+			Err(e) => { eprintln!("{}", e); },
+		}
+	}
+}
+
+fn main() {
+	let mut s = Sketch::new(640, 480, "Drop an image onto the window");
+	let mut v = Viewer { img: None };
+	sdl2sketch::run(&mut s, &mut v);
+}
@@ -0,0 +1,47 @@
+extern crate sdl2sketch;
+use sdl2sketch::*;
+
+struct PixelCar {
+	img: Option<Image>,
+	pos: (i32, i32),
+}
+
+impl MainLoopMethods for PixelCar {
+	fn setup(&mut self, s: &mut Sketch) {
+		s.set_framerate(30);
+		s.resizable(true);
+		s.pixel_art(160, 100);
+		// images have to be loaded after enabling the pixel-art mode to use nearest-neighbor filtering
+		self.img = Some(s.load_image(std::path::Path::new("examples/pixelcar_64x40.png")));
+	}
+
+	fn update(&mut self, s: &mut Sketch) {
+		self.pos.0 += 1;
+		if self.pos.0 > s.width() {
+			self.pos.0 = -64;
+		}
+	}
+
+	fn draw(&mut self, s: &mut Sketch) {
+		s.background(Color::RGB(33, 33, 33));
+		if let Some(ref img) = self.img {
+			s.image(img, self.pos.0, self.pos.1, 0, 0);
+		}
+		let (mx, my) = s.mouse_pos();
+		s.stroke(Color::RGB(255, 0, 0));
+		s.point(mx, my);
+	}
+
+	fn mouse_pressed(&mut self, _s: &mut Sketch, button: MouseButton, x: i32, y: i32) {
+		println!("Mouse \"{:?}\" pressed at logical position ({},{})", button, x, y);
+	}
+}
+
+fn main() {
+	let mut s = Sketch::new(960, 600, "Pixel-art mode");
+	let mut p = PixelCar {
+		img: None,
+		pos: (0, 30),
+	};
+	sdl2sketch::run(&mut s, &mut p);
+}
@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use sdl2::keyboard::{Keycode, Scancode};
+use sdl2::mouse::MouseButton;
+use sdl2::controller::{Axis, Button};
+
+use input::InputState;
+
+/// name of the action which quits the main loop (bound to Escape by default)
+pub const QUIT_ACTION: &str = "quit";
+
+
+/// a digital input which can be bound to an action (see Sketch::bind_action())
+///
+/// In a bindings file a binding is written as "key:Space", "scancode:W", "mouse:Left" or "controller_button:a". Keys and scancodes use the SDL key names, controller buttons the names of the SDL game controller mappings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Binding {
+	/// key identified by its keycode (depends on the keyboard layout)
+	Key(Keycode),
+	/// key identified by its scancode (physical position of the key)
+	Scancode(Scancode),
+	/// mouse button
+	MouseButton(MouseButton),
+	/// button of a game controller
+	ControllerButton(Button),
+}
+
+impl Binding {
+	fn is_down(&self, input: &InputState) -> bool {
+		match *self {
+			Binding::Key(code)                => input.keys_down.contains(&code),
+			Binding::Scancode(code)           => input.scancodes_down.contains(&code),
+			Binding::MouseButton(button)      => input.mouse_buttons_down.contains(&button),
+			Binding::ControllerButton(button) => input.any_controller_button_is_down(button),
+		}
+	}
+
+	fn went_down(&self, input: &InputState) -> bool {
+		match *self {
+			Binding::Key(code)                => input.keys_went_down.contains(&code),
+			Binding::Scancode(code)           => input.scancodes_went_down.contains(&code),
+			Binding::MouseButton(button)      => input.mouse_buttons_went_down.contains(&button),
+			Binding::ControllerButton(button) => input.controller_buttons_went_down.contains(&button),
+		}
+	}
+
+	fn went_up(&self, input: &InputState) -> bool {
+		match *self {
+			Binding::Key(code)                => input.keys_went_up.contains(&code),
+			Binding::Scancode(code)           => input.scancodes_went_up.contains(&code),
+			Binding::MouseButton(button)      => input.mouse_buttons_went_up.contains(&button),
+			Binding::ControllerButton(button) => input.controller_buttons_went_up.contains(&button),
+		}
+	}
+}
+
+impl fmt::Display for Binding {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Binding::Key(code)                => write!(f, "key:{}", code.name()),
+			Binding::Scancode(code)           => write!(f, "scancode:{}", code.name()),
+			Binding::MouseButton(button)      => write!(f, "mouse:{}", mouse_button_name(button)),
+			Binding::ControllerButton(button) => write!(f, "controller_button:{}", button.string()),
+		}
+	}
+}
+
+impl FromStr for Binding {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (kind, name) = split_binding(s)?;
+		let binding = match kind {
+			"key"               => Keycode::from_name(name).map(Binding::Key),
+			"scancode"          => Scancode::from_name(name).map(Binding::Scancode),
+			"mouse"             => mouse_button_from_name(name).map(Binding::MouseButton),
+			"controller_button" => Button::from_string(name).map(Binding::ControllerButton),
+			_ => { return Err(format!("unknown binding type \"{}\"", kind)); }
+		};
+		binding.ok_or_else( || format!("unknown {} \"{}\"", kind, name) )
+	}
+}
+
+
+/// an input which can be bound to an axis (see Sketch::bind_axis())
+///
+/// In a bindings file an axis binding is written as "negative key:Left", "positive key:Right" or "controller_axis:leftx".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AxisBinding {
+	/// digital input which sets the axis to -1.0 while it is pressed
+	Negative(Binding),
+	/// digital input which sets the axis to 1.0 while it is pressed
+	Positive(Binding),
+	/// analog axis of a game controller (-1.0 to 1.0, the axis with the largest deflection of all connected controllers)
+	ControllerAxis(Axis),
+}
+
+impl AxisBinding {
+	fn value(&self, input: &InputState) -> f32 {
+		match *self {
+			AxisBinding::Negative(ref b)      => if b.is_down(input) { -1.0 } else { 0.0 },
+			AxisBinding::Positive(ref b)      => if b.is_down(input) { 1.0 } else { 0.0 },
+			AxisBinding::ControllerAxis(axis) => input.any_controller_axis(axis),
+		}
+	}
+}
+
+impl fmt::Display for AxisBinding {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			AxisBinding::Negative(ref b)      => write!(f, "negative {}", b),
+			AxisBinding::Positive(ref b)      => write!(f, "positive {}", b),
+			AxisBinding::ControllerAxis(axis) => write!(f, "controller_axis:{}", axis.string()),
+		}
+	}
+}
+
+impl FromStr for AxisBinding {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		if let Some(b) = strip_word(s, "negative") {
+			return b.parse().map(AxisBinding::Negative);
+		}
+		if let Some(b) = strip_word(s, "positive") {
+			return b.parse().map(AxisBinding::Positive);
+		}
+		let (kind, name) = split_binding(s)?;
+		if kind != "controller_axis" {
+			return Err(format!("axis binding \"{}\" needs to be \"controller_axis:...\" or start with \"negative\" or \"positive\"", s));
+		}
+		Axis::from_string(name).map(AxisBinding::ControllerAxis).ok_or_else( || format!("unknown controller_axis \"{}\"", name) )
+	}
+}
+
+
+/// This struct contains the bindings of named actions and axes to inputs.
+pub struct ActionMap {
+	actions: BTreeMap<String, Vec<Binding>>,
+	axes: BTreeMap<String, Vec<AxisBinding>>,
+}
+
+impl ActionMap {
+	/// creates the default bindings, i.e. the quit action bound to Escape
+	pub fn new() -> Self {
+		let mut map = ActionMap {
+			actions: BTreeMap::new(),
+			axes: BTreeMap::new(),
+		};
+		map.bind_action(QUIT_ACTION, Binding::Key(Keycode::Escape));
+		map
+	}
+
+	pub fn bind_action(&mut self, name: &str, binding: Binding) {
+		let bindings = self.actions.entry(name.to_string()).or_default();
+		if !bindings.contains(&binding) {
+			bindings.push(binding);
+		}
+	}
+
+	pub fn bind_axis(&mut self, name: &str, binding: AxisBinding) {
+		let bindings = self.axes.entry(name.to_string()).or_default();
+		if !bindings.contains(&binding) {
+			bindings.push(binding);
+		}
+	}
+
+	pub fn unbind_action(&mut self, name: &str) {
+		self.actions.remove(name);
+	}
+
+	pub fn unbind_axis(&mut self, name: &str) {
+		self.axes.remove(name);
+	}
+
+	pub fn action_down(&self, name: &str, input: &InputState) -> bool {
+		self.actions.get(name).is_some_and( |bindings| bindings.iter().any( |b| b.is_down(input) ) )
+	}
+
+	pub fn action_went_down(&self, name: &str, input: &InputState) -> bool {
+		self.actions.get(name).is_some_and( |bindings| bindings.iter().any( |b| b.went_down(input) ) )
+	}
+
+	pub fn action_went_up(&self, name: &str, input: &InputState) -> bool {
+		self.actions.get(name).is_some_and( |bindings| {
+			bindings.iter().any( |b| b.went_up(input) ) && !bindings.iter().any( |b| b.is_down(input) )
+		})
+	}
+
+	/// returns the sum of all bound inputs constrained to [-1.0, 1.0]
+	pub fn axis(&self, name: &str, input: &InputState) -> f32 {
+		let sum: f32 = match self.axes.get(name) {
+			Some(bindings) => bindings.iter().map( |b| b.value(input) ).sum(),
+			None => 0.0,
+		};
+		::utils::constrain(sum, -1.0, 1.0)
+	}
+
+	/// parses bindings in the format written by fmt::Display (replaces all current bindings)
+	///
+	/// Each line contains one binding: "action <name> = <binding>" or "axis <name> = <axis binding>". Empty lines and lines starting with '#' are ignored.
+	/// Names which are empty or contain whitespace, '=' or '"' are written in quotes with '"' and '\\' escaped by a backslash, e.g. action "jump high" = key:Space.
+	pub fn parse(&mut self, text: &str) -> Result<(), String> {
+		let mut map = ActionMap {
+			actions: BTreeMap::new(),
+			axes: BTreeMap::new(),
+		};
+		for (i, line) in text.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			let (kind, rest) = line.split_at(line.find(char::is_whitespace).unwrap_or(line.len()));
+			let (name, rest) = parse_name(rest.trim_start()).map_err( |e| format!("line {}: {}", i+1, e) )?;
+			let rhs = match rest.trim_start().strip_prefix('=') {
+				Some(rhs) => rhs.trim(),
+				None if rest.contains('=') || name.is_none() => { return Err(format!("line {}: expected \"action <name>\" or \"axis <name>\" before '='", i+1)); },
+				None => { return Err(format!("line {}: missing '='", i+1)); }
+			};
+			match (kind, name) {
+				("action", Some(name)) => { map.bind_action(&name, rhs.parse().map_err( |e| format!("line {}: {}", i+1, e) )?); },
+				("axis", Some(name))   => { map.bind_axis(&name, rhs.parse().map_err( |e| format!("line {}: {}", i+1, e) )?); },
+				_ => { return Err(format!("line {}: expected \"action <name>\" or \"axis <name>\" before '='", i+1)); }
+			}
+		}
+		*self = map;
+		Ok(())
+	}
+}
+
+impl fmt::Display for ActionMap {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (name, bindings) in &self.actions {
+			for b in bindings {
+				writeln!(f, "action {} = {}", quote_name(name), b)?;
+			}
+		}
+		for (name, bindings) in &self.axes {
+			for b in bindings {
+				writeln!(f, "axis {} = {}", quote_name(name), b)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+
+/// returns the name as written in a bindings file, i.e. in quotes if it cannot be written as a single word
+fn quote_name(name: &str) -> String {
+	if !name.is_empty() && !name.contains( |c: char| c.is_whitespace() || c == '=' || c == '"' ) {
+		return name.to_string();
+	}
+	let mut quoted = String::from("\"");
+	for c in name.chars() {
+		if c == '"' || c == '\\' {
+			quoted.push('\\');
+		}
+		quoted.push(c);
+	}
+	quoted.push('"');
+	quoted
+}
+
+/// reads a name written by quote_name() from the start of s and returns it with the rest of s (None if s does not start with a name)
+fn parse_name(s: &str) -> Result<(Option<String>, &str), String> {
+	if let Some(quoted) = s.strip_prefix('"') {
+		let mut name = String::new();
+		let mut chars = quoted.char_indices();
+		while let Some((i, c)) = chars.next() {
+			match c {
+				'"'  => { return Ok((Some(name), &quoted[i+1..])); },
+				'\\' => { name.extend(chars.next().map( |(_, c)| c )); },
+				c    => { name.push(c); }
+			}
+		}
+		return Err(format!("missing '\"' after the name {}", s));
+	}
+	let end = s.find( |c: char| c.is_whitespace() || c == '=' ).unwrap_or(s.len());
+	let (name, rest) = s.split_at(end);
+	Ok((if name.is_empty() { None } else { Some(name.to_string()) }, rest))
+}
+
+/// splits a binding like "key:Space" into its type and name
+fn split_binding(s: &str) -> Result<(&str, &str), String> {
+	let mut parts = s.trim().splitn(2, ':');
+	match (parts.next(), parts.next()) {
+		(Some(kind), Some(name)) if !name.is_empty() => Ok((kind.trim(), name)),
+		_ => Err(format!("binding \"{}\" needs to be in the format \"<type>:<name>\"", s)),
+	}
+}
+
+/// returns the rest of s, if s starts with the provided word followed by whitespace
+fn strip_word<'a>(s: &'a str, word: &str) -> Option<&'a str> {
+	match s.strip_prefix(word) {
+		Some(rest) if rest.starts_with(char::is_whitespace) => Some(rest.trim_start()),
+		_ => None,
+	}
+}
+
+pub fn mouse_button_name(button: MouseButton) -> &'static str {
+	match button {
+		MouseButton::Left    => "Left",
+		MouseButton::Middle  => "Middle",
+		MouseButton::Right   => "Right",
+		MouseButton::X1      => "X1",
+		MouseButton::X2      => "X2",
+		MouseButton::Unknown => "Unknown",
+	}
+}
+
+pub fn mouse_button_from_name(name: &str) -> Option<MouseButton> {
+	match name {
+		"Left"   => Some(MouseButton::Left),
+		"Middle" => Some(MouseButton::Middle),
+		"Right"  => Some(MouseButton::Right),
+		"X1"     => Some(MouseButton::X1),
+		"X2"     => Some(MouseButton::X2),
+		_ => None,
+	}
+}
@@ -0,0 +1,240 @@
+use std::f32::consts::PI;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use synth::DEFAULT_SAMPLE_RATE;
+
+const MIN_BINS: usize = 16;
+const MAX_BINS: usize = 8192;
+
+
+/// latest samples of the analyzed audio, oldest first starting at pos
+struct SampleBuffer {
+	samples: Vec<f32>,
+	pos: usize,
+}
+
+impl SampleBuffer {
+	fn new(size: usize) -> Self {
+		SampleBuffer { samples: vec![0.0; size], pos: 0 }
+	}
+
+	fn push(&mut self, samples: &[f32]) {
+		let size = self.samples.len();
+		let skip = samples.len().saturating_sub(size); // older samples would be overwritten anyway
+		for sample in &samples[skip..] {
+			self.samples[self.pos] = *sample;
+			self.pos = (self.pos + 1) % size;
+		}
+	}
+
+	fn ordered(&self) -> Vec<f32> {
+		let mut samples = Vec::with_capacity(self.samples.len());
+		samples.extend_from_slice(&self.samples[self.pos..]);
+		samples.extend_from_slice(&self.samples[..self.pos]);
+		samples
+	}
+
+	fn resize(&mut self, size: usize) {
+		let mut samples = self.ordered();
+		let len = samples.len();
+		if size <= len {
+			samples.drain(..len - size);
+		} else {
+			samples.splice(..0, vec![0.0; size - len]);
+		}
+		self.samples = samples;
+		self.pos = 0;
+	}
+}
+
+
+/// This struct contains a handle to feed samples into an analyzer, e.g. from an audio callback.
+///
+/// Clones feed the same analyzer.
+#[derive(Clone)]
+pub struct AnalyzerInput {
+	buffer: Arc<Mutex<SampleBuffer>>,
+	sample_rate: Arc<AtomicI32>, // shared, so the source of the samples can change it (e.g. to the rate granted by the audio device)
+}
+
+impl AnalyzerInput {
+	/// adds mono samples in [-1.0, 1.0] to the analyzed audio
+	///
+	/// This never waits for the analyzer: if it is busy (e.g. copying the samples for spectrum()), the samples are dropped. So it can be called from an audio callback.
+	pub fn push_samples(&self, samples: &[f32]) {
+		if let Ok(mut buffer) = self.buffer.try_lock() {
+			buffer.push(samples);
+		}
+	}
+
+	/// sets the sample rate of the analyzed audio in Hz (see Analyzer::set_sample_rate())
+	pub fn set_sample_rate(&self, sample_rate: i32) {
+		self.sample_rate.store(sample_rate, Ordering::Relaxed);
+	}
+}
+
+
+/// This struct contains an analyzer for the spectrum and the level of audio.
+///
+/// The analyzer always looks at the latest 2 * bins samples, which are added with push_samples() or by attaching it to a synth (Synth::attach_analyzer()) or to the audio output (Sketch::attach_analyzer(), needs the feature "audio").
+/// spectrum() and level() smooth their results over time, so they should be called once per frame.
+pub struct Analyzer {
+	input: AnalyzerInput,
+	bins: usize,
+	smoothing: f32,
+	spectrum: Vec<f32>,
+	level: f32,
+}
+
+impl Analyzer {
+	/// creates an analyzer with the given number of frequency bins (a power of two between 16 and 8192) and smoothing in [0.0, 1.0) (0.0 = no smoothing)
+	pub fn new(bins: usize, smoothing: f32) -> Self {
+		let bins = valid_bins(bins);
+		Analyzer {
+			input: AnalyzerInput {
+				buffer: Arc::new(Mutex::new(SampleBuffer::new(2 * bins))),
+				sample_rate: Arc::new(AtomicI32::new(DEFAULT_SAMPLE_RATE)),
+			},
+			bins,
+			smoothing: valid_smoothing(smoothing),
+			spectrum: vec![0.0; bins],
+			level: 0.0,
+		}
+	}
+
+	/// returns a handle to add samples from another thread
+	pub fn input(&self) -> AnalyzerInput {
+		self.input.clone()
+	}
+
+	/// adds mono samples in [-1.0, 1.0] to the analyzed audio
+	///
+	/// Unlike AnalyzerInput::push_samples() this waits until the analyzer is not busy, so no samples are dropped.
+	pub fn push_samples(&self, samples: &[f32]) {
+		if let Ok(mut buffer) = self.input.buffer.lock() {
+			buffer.push(samples);
+		}
+	}
+
+	/// sets the number of frequency bins (rounded up to a power of two between 16 and 8192)
+	pub fn set_bins(&mut self, bins: usize) {
+		self.bins = valid_bins(bins);
+		self.spectrum = vec![0.0; self.bins];
+		if let Ok(mut buffer) = self.input.buffer.lock() {
+			buffer.resize(2 * self.bins);
+		}
+	}
+
+	pub fn bins(&self) -> usize {
+		self.bins
+	}
+
+	/// sets the smoothing of spectrum() and level() in [0.0, 1.0) (0.0 = no smoothing)
+	pub fn set_smoothing(&mut self, smoothing: f32) {
+		self.smoothing = valid_smoothing(smoothing);
+	}
+
+	pub fn smoothing(&self) -> f32 {
+		self.smoothing
+	}
+
+	/// sets the sample rate of the analyzed audio in Hz (only needed for bin_frequency())
+	pub fn set_sample_rate(&mut self, sample_rate: i32) {
+		self.input.set_sample_rate(sample_rate);
+	}
+
+	pub fn sample_rate(&self) -> i32 {
+		self.input.sample_rate.load(Ordering::Relaxed)
+	}
+
+	/// returns the center frequency in Hz of a bin of the spectrum
+	pub fn bin_frequency(&self, bin: usize) -> f32 {
+		bin as f32 * self.sample_rate() as f32 / (2 * self.bins) as f32
+	}
+
+	/// returns the latest 2 * bins samples, oldest first
+	pub fn waveform(&self) -> Vec<f32> {
+		match self.input.buffer.lock() {
+			Ok(buffer) => buffer.ordered(),
+			Err(_) => vec![0.0; 2 * self.bins],
+		}
+	}
+
+	/// returns the amplitude of each frequency bin from 0 Hz up to half the sample rate
+	///
+	/// A sine wave with amplitude 1.0 results in a value of about 1.0 in the bin of its frequency.
+	pub fn spectrum(&mut self) -> &[f32] {
+		let samples = self.waveform();
+		let n = samples.len();
+		let mut re: Vec<f32> = samples.iter().enumerate().map( |(i, x)| x * hann(i, n) ).collect();
+		let mut im = vec![0.0; n];
+		fft(&mut re, &mut im);
+		for (bin, value) in self.spectrum.iter_mut().enumerate() {
+			let amplitude = 4.0 * (re[bin] * re[bin] + im[bin] * im[bin]).sqrt() / n as f32; // 2 / sum of the window
+			*value = self.smoothing * *value + (1.0 - self.smoothing) * amplitude;
+		}
+		&self.spectrum
+	}
+
+	/// returns the RMS level of the latest samples in [0.0, 1.0]
+	pub fn level(&mut self) -> f32 {
+		let samples = self.waveform();
+		let rms = (samples.iter().map( |x| x * x ).sum::<f32>() / samples.len() as f32).sqrt();
+		self.level = self.smoothing * self.level + (1.0 - self.smoothing) * rms;
+		self.level
+	}
+}
+
+
+fn valid_bins(bins: usize) -> usize {
+	::utils::constrain(bins.next_power_of_two(), MIN_BINS, MAX_BINS)
+}
+
+fn valid_smoothing(smoothing: f32) -> f32 {
+	::utils::constrain(smoothing, 0.0, 0.99)
+}
+
+/// Hann window
+fn hann(i: usize, n: usize) -> f32 {
+	0.5 - 0.5 * (2.0 * PI * i as f32 / n as f32).cos()
+}
+
+/// in-place radix-2 FFT (the length must be a power of two)
+fn fft(re: &mut [f32], im: &mut [f32]) {
+	let n = re.len();
+
+	// bit-reversal permutation
+	let mut j = 0;
+	for i in 1..n {
+		let mut bit = n >> 1;
+		while j & bit != 0 {
+			j ^= bit;
+			bit >>= 1;
+		}
+		j |= bit;
+		if i < j {
+			re.swap(i, j);
+			im.swap(i, j);
+		}
+	}
+
+	let mut len = 2;
+	while len <= n {
+		let angle = -2.0 * PI / len as f32;
+		for start in (0..n).step_by(len) {
+			for k in 0..len / 2 {
+				let (sin, cos) = (angle * k as f32).sin_cos();
+				let a = start + k;
+				let b = a + len / 2;
+				let t_re = re[b] * cos - im[b] * sin;
+				let t_im = re[b] * sin + im[b] * cos;
+				re[b] = re[a] - t_re;
+				im[b] = im[a] - t_im;
+				re[a] += t_re;
+				im[a] += t_im;
+			}
+		}
+		len <<= 1;
+	}
+}
@@ -0,0 +1,286 @@
+use std::cell::RefCell;
+use std::os::raw::{c_int, c_void};
+use std::path::Path;
+use std::rc::Rc;
+use std::ptr;
+use std::slice;
+use sdl2::mixer::{self, Channel, Chunk};
+
+use Sketch;
+use analyzer::{Analyzer, AnalyzerInput};
+
+// not wrapped by rust-sdl2 yet
+extern "C" {
+	fn Mix_SetPostMix(mix_func: Option<unsafe extern "C" fn(udata: *mut c_void, stream: *mut u8, len: c_int)>, arg: *mut c_void);
+}
+
+/// sample rate of the audio output in Hz
+pub const SAMPLE_RATE: i32 = 44_100;
+
+/// number of sample frames mixed at once
+const CHUNK_SIZE: i32 = 1024;
+
+/// music loaded with Sketch::load_music()
+pub type Music = mixer::Music<'static>;
+
+
+/// This struct contains a sound loaded with Sketch::load_sound().
+///
+/// Each sound is played on its own mixer channel, so it can be stopped, panned and changed in volume independently of other sounds. Playing a sound again restarts it.
+/// When the sound is dropped, its channel is reused by the next loaded sound.
+pub struct Sound {
+	chunk: Chunk,
+	channel: i32,
+	free_channels: Rc<RefCell<Vec<i32>>>,
+}
+
+impl Drop for Sound {
+	fn drop(&mut self) {
+		self.free_channels.borrow_mut().push(self.channel);
+	}
+}
+
+impl Sound {
+	/// returns the decoded samples of the sound mixed down to mono
+	///
+	/// The samples have the sample rate of the audio output (see audio_sample_rate()), so they can be analyzed offline by Analyzer::push_samples().
+	pub fn samples(&self) -> Vec<f32> {
+		let channels = mixer::query_spec().map( |(_, _, channels)| channels as usize ).unwrap_or(2);
+		let chunk = unsafe { &*self.chunk.raw };
+		let data = unsafe { slice::from_raw_parts(chunk.abuf as *const i16, chunk.alen as usize / 2) };
+		let mut samples = Vec::with_capacity(data.len() / channels.max(1));
+		to_mono(data, channels, &mut samples);
+		samples
+	}
+}
+
+
+/// This struct contains the state of the audio output of a sketch.
+pub struct AudioState {
+	_mixer_context: Option<mixer::Sdl2MixerContext>,
+	_audio_subsystem: Option<sdl2::AudioSubsystem>,
+	open: bool,
+	master_volume: f32,
+	music_volume: f32,
+	channel_volumes: Vec<f32>, // volume of the sound of each channel
+	free_channels: Rc<RefCell<Vec<i32>>>, // channels of dropped sounds
+	analyzer: Option<Box<PostMix>>,
+}
+
+/// data of the post mix callback, which feeds the audio output into an analyzer
+struct PostMix {
+	input: AnalyzerInput,
+	channels: usize,
+	mono: Vec<f32>, // preallocated buffer for the samples of one callback
+}
+
+impl AudioState {
+	/// initializes the SDL2 audio subsystem and opens the mixer
+	///
+	/// If this fails (e.g. there is no audio device), the sketch runs without sound. With SDL's dummy audio driver (SDL_AUDIODRIVER=dummy) the sound is played silently.
+	pub fn new(sdl_context: &sdl2::Sdl) -> Self {
+		let audio_subsystem = sdl_context.audio().map_err( |e| { eprintln!("Initialization of SDL2 audio subsystem failed. {}", e); } ).ok();
+		let open = audio_subsystem.is_some() && mixer::open_audio(SAMPLE_RATE, mixer::DEFAULT_FORMAT, mixer::DEFAULT_CHANNELS, CHUNK_SIZE)
+			.map_err( |e| { eprintln!("SDL2-mixer open_audio() failed. {}", e); } ).is_ok();
+		let mixer_context = mixer::init(mixer::INIT_OGG | mixer::INIT_MP3 | mixer::INIT_FLAC)
+			.map_err( |e| { eprintln!("SDL2-mixer init() failed, only WAV files can be played. {}", e); } ).ok();
+		AudioState {
+			_mixer_context: mixer_context,
+			_audio_subsystem: audio_subsystem,
+			open,
+			master_volume: 1.0,
+			music_volume: 1.0,
+			channel_volumes: Vec::new(),
+			free_channels: Rc::new(RefCell::new(Vec::new())),
+			analyzer: None,
+		}
+	}
+
+	/// returns a channel for a new sound, either the channel of a dropped sound or a newly allocated one
+	fn allocate_channel(&mut self) -> i32 {
+		let channel = match self.free_channels.borrow_mut().pop() {
+			Some(channel) => channel,
+			None => {
+				let channel = self.channel_volumes.len() as i32;
+				mixer::allocate_channels(channel + 1);
+				self.channel_volumes.push(1.0);
+				channel
+			}
+		};
+		self.channel_volumes[channel as usize] = 1.0;
+		channel
+	}
+
+	/// sets the volume of the channel with the master volume applied
+	fn apply_channel_volume(&self, channel: i32) {
+		if let Some(volume) = self.channel_volumes.get(channel as usize) {
+			Channel(channel).set_volume(to_mixer_volume(volume * self.master_volume));
+		}
+	}
+
+	fn apply_music_volume(&self) {
+		mixer::Music::set_volume(to_mixer_volume(self.music_volume * self.master_volume));
+	}
+}
+
+impl Drop for AudioState {
+	fn drop(&mut self) {
+		if self.analyzer.is_some() {
+			unsafe { Mix_SetPostMix(None, ptr::null_mut()) };
+		}
+		if self.open {
+			mixer::close_audio();
+		}
+	}
+}
+
+
+impl Sketch {
+
+	/* audio */
+
+	/// loads a sound from a WAV, OGG, MP3 or FLAC file (panics if this fails)
+	pub fn load_sound(&mut self, filename: &Path) -> Sound {
+		self.try_load_sound(filename).expect("Loading of sound file failed. Abort.")
+	}
+
+	/// loads a sound from a WAV, OGG, MP3 or FLAC file
+	///
+	/// Unlike load_sound() this does not panic, but returns an error message.
+	pub fn try_load_sound(&mut self, filename: &Path) -> Result<Sound, String> {
+		if !self.audio.open {
+			return Err("The audio output is not available.".to_string());
+		}
+		let chunk = Chunk::from_file(filename)?;
+		let channel = self.audio.allocate_channel();
+		self.audio.apply_channel_volume(channel);
+		Ok(Sound { chunk, channel, free_channels: self.audio.free_channels.clone() })
+	}
+
+	/// plays the sound once
+	pub fn play_sound(&mut self, sound: &Sound) {
+		Channel(sound.channel).play(&sound.chunk, 0).unwrap_or_else( |e| { eprintln!("SDL2-mixer play() failed. {}", e); Channel(-1) } );
+	}
+
+	/// plays the sound in an endless loop until stop_sound() is called
+	///
+	/// Since "loop" is a keyword in rust, the function cannot be called loop() as in the p5.sound API.
+	pub fn loop_sound(&mut self, sound: &Sound) {
+		Channel(sound.channel).play(&sound.chunk, -1).unwrap_or_else( |e| { eprintln!("SDL2-mixer play() failed. {}", e); Channel(-1) } );
+	}
+
+	/// stops playing the sound
+	pub fn stop_sound(&mut self, sound: &Sound) {
+		Channel(sound.channel).halt();
+	}
+
+	/// checks if the sound is currently playing
+	pub fn sound_is_playing(&self, sound: &Sound) -> bool {
+		Channel(sound.channel).is_playing()
+	}
+
+	/// sets the volume of the sound between 0.0 (silent) and 1.0 (default)
+	pub fn sound_volume(&mut self, sound: &Sound, volume: f32) {
+		if let Some(channel_volume) = self.audio.channel_volumes.get_mut(sound.channel as usize) {
+			*channel_volume = volume;
+		}
+		self.audio.apply_channel_volume(sound.channel);
+	}
+
+	/// sets the stereo position of the sound between -1.0 (left) and 1.0 (right), default: 0.0 (center)
+	pub fn sound_pan(&mut self, sound: &Sound, pan: f32) {
+		let pan = ::utils::constrain(pan, -1.0, 1.0);
+		let left = (255.0 * (1.0 - pan).min(1.0)) as u8;
+		let right = (255.0 * (1.0 + pan).min(1.0)) as u8;
+		Channel(sound.channel).set_panning(left, right).unwrap_or_else( |e| { eprintln!("SDL2-mixer set_panning() failed. {}", e); } );
+	}
+
+	/// loads music from a WAV, OGG, MP3 or FLAC file
+	///
+	/// Unlike sounds, music is streamed from the file while it is played and there is only one music channel, i.e. playing music stops the music played before.
+	pub fn load_music(&mut self, filename: &Path) -> Result<Music, String> {
+		if !self.audio.open {
+			return Err("The audio output is not available.".to_string());
+		}
+		mixer::Music::from_file(filename)
+	}
+
+	/// plays the music on the music channel, once or in an endless loop
+	pub fn play_music(&mut self, music: &Music, looping: bool) {
+		music.play(if looping { -1 } else { 1 }).unwrap_or_else( |e| { eprintln!("SDL2-mixer play() failed. {}", e); } );
+	}
+
+	/// stops the music channel
+	pub fn stop_music(&mut self) {
+		mixer::Music::halt();
+	}
+
+	/// sets the volume of the music channel between 0.0 (silent) and 1.0 (default)
+	pub fn music_volume(&mut self, volume: f32) {
+		self.audio.music_volume = volume;
+		self.audio.apply_music_volume();
+	}
+
+	/// sets the volume of all sounds and the music between 0.0 (silent) and 1.0 (default)
+	///
+	/// The volumes of the sounds and the music are multiplied by the master volume.
+	pub fn master_volume(&mut self, volume: f32) {
+		self.audio.master_volume = volume;
+		for channel in 0..self.audio.channel_volumes.len() {
+			self.audio.apply_channel_volume(channel as i32);
+		}
+		self.audio.apply_music_volume();
+	}
+
+	/// returns the sample rate of the audio output in Hz
+	pub fn audio_sample_rate(&self) -> i32 {
+		mixer::query_spec().map( |(freq, _, _)| freq ).unwrap_or(SAMPLE_RATE)
+	}
+
+	/// feeds the audio output, i.e. all playing sounds and the music, into the analyzer
+	///
+	/// Only one analyzer can be attached to the audio output, attaching another one detaches the current one.
+	pub fn attach_analyzer(&mut self, analyzer: &mut Analyzer) {
+		if !self.audio.open {
+			return;
+		}
+		self.detach_analyzer();
+		let (freq, _, channels) = mixer::query_spec().unwrap_or((SAMPLE_RATE, mixer::DEFAULT_FORMAT, 2));
+		analyzer.set_sample_rate(freq);
+		let mono = Vec::with_capacity(CHUNK_SIZE as usize);
+		let mut post_mix = Box::new(PostMix { input: analyzer.input(), channels: channels as usize, mono });
+		unsafe { Mix_SetPostMix(Some(post_mix_callback), &mut *post_mix as *mut PostMix as *mut c_void) };
+		self.audio.analyzer = Some(post_mix);
+	}
+
+	/// stops feeding the audio output into the analyzer attached with attach_analyzer()
+	pub fn detach_analyzer(&mut self) {
+		if let Some(post_mix) = self.audio.analyzer.take() {
+			unsafe { Mix_SetPostMix(None, ptr::null_mut()) }; // waits for a running callback
+			drop(post_mix);
+		}
+	}
+}
+
+
+/// called by SDL2-mixer with the mixed audio output in the format opened by AudioState::new()
+///
+/// The samples are converted in a preallocated buffer and dropped if the analyzer is busy, so the callback neither allocates nor waits for a lock.
+unsafe extern "C" fn post_mix_callback(udata: *mut c_void, stream: *mut u8, len: c_int) {
+	let post_mix = &mut *(udata as *mut PostMix);
+	let data = slice::from_raw_parts(stream as *const i16, len as usize / 2);
+	to_mono(data, post_mix.channels, &mut post_mix.mono);
+	post_mix.input.push_samples(&post_mix.mono);
+}
+
+/// converts interleaved 16 bit samples to mono samples in [-1.0, 1.0], which replace the content of out
+fn to_mono(data: &[i16], channels: usize, out: &mut Vec<f32>) {
+	let channels = channels.max(1);
+	out.clear();
+	out.extend(data.chunks(channels).map( |frame| frame.iter().map( |x| *x as f32 ).sum::<f32>() / (channels as f32 * 32_768.0) ));
+}
+
+/// converts a volume in [0.0, 1.0] to the range of SDL2-mixer
+fn to_mixer_volume(volume: f32) -> i32 {
+	(::utils::constrain(volume, 0.0, 1.0) * mixer::MAX_VOLUME as f32).round() as i32
+}
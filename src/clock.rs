@@ -0,0 +1,57 @@
+use sdl2_sys::SDL_GetTicks;
+
+/// This struct provides the time since the start of a sketch in ms (see Sketch::clock_mode()).
+///
+/// The real-time clock uses SDL_GetTicks(). The frame-based (virtual) clock advances by exactly the duration of one frame (1/fps) per frame, so the time does not depend on the speed of the computer.
+/// The frame-based time is kept with fractions of a ms and only rounded when it is read with now(), so the frames at e.g. 30 fps do not drift.
+pub struct Clock {
+	frame_based: bool,
+	start: u32, // SDL ticks at the start of the real-time clock
+	time: f64, // in ms, time of the frame-based clock
+}
+
+impl Clock {
+	pub fn new() -> Self {
+		Clock {
+			frame_based: false,
+			start: unsafe { SDL_GetTicks() },
+			time: 0.0,
+		}
+	}
+
+	/// resets the time to 0
+	pub fn restart(&mut self) {
+		self.start = unsafe { SDL_GetTicks() };
+		self.time = 0.0;
+	}
+
+	pub fn set_frame_based(&mut self, setting: bool) {
+		if setting && !self.frame_based {
+			self.time = self.now_exact(); // continue with the current time
+		} else if !setting && self.frame_based {
+			self.start = unsafe { SDL_GetTicks() }.wrapping_sub(self.now());
+		}
+		self.frame_based = setting;
+	}
+
+	/// advances the frame-based clock by the duration of one frame
+	pub fn advance_frame(&mut self, fps: u32) {
+		if self.frame_based && fps > 0 {
+			self.time += 1000.0 / fps as f64;
+		}
+	}
+
+	/// returns the time since the start in ms
+	pub fn now(&self) -> u32 {
+		self.now_exact().round() as u32
+	}
+
+	/// returns the time since the start in ms including fractions of the frame-based clock
+	pub fn now_exact(&self) -> f64 {
+		if self.frame_based {
+			self.time
+		} else {
+			unsafe { SDL_GetTicks() }.wrapping_sub(self.start) as f64
+		}
+	}
+}
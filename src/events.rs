@@ -0,0 +1,120 @@
+use std::mem;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use sdl2::event::Event;
+use sdl2_sys::{SDL_Event, SDL_EventType, SDL_TouchFingerEvent, SDL_MultiGestureEvent, SDL_TextInputEvent, SDL_TextEditingEvent, SDL_DropEvent};
+
+/// size of the text buffer of TextInput and TextEditing events (including the terminating NUL)
+const TEXT_SIZE: usize = 32;
+
+
+/// adds the event to the end of the event queue of SDL
+///
+/// rust-sdl2 0.31 cannot convert text, touch, gesture and drop events for SDL_PushEvent(), so these are converted here. Like SDL does, a text longer than the buffer of a TextInput event is split into several events.
+pub fn push_event(event_subsystem: &sdl2::EventSubsystem, event: Event) -> Result<(), String> {
+	match event {
+		Event::TextInput { timestamp, window_id, ref text } => {
+			for part in split_text(text, TEXT_SIZE - 1) {
+				let mut raw: SDL_Event = unsafe { mem::zeroed() };
+				raw.text = SDL_TextInputEvent {
+					type_: SDL_EventType::SDL_TEXTINPUT as u32,
+					timestamp,
+					windowID: window_id,
+					text: text_buffer(part),
+				};
+				push_raw_event(raw)?;
+			}
+			Ok(())
+		},
+		Event::TextEditing { timestamp, window_id, ref text, start, length } => {
+			let mut raw: SDL_Event = unsafe { mem::zeroed() };
+			raw.edit = SDL_TextEditingEvent {
+				type_: SDL_EventType::SDL_TEXTEDITING as u32,
+				timestamp,
+				windowID: window_id,
+				text: text_buffer(split_text(text, TEXT_SIZE - 1).next().unwrap_or("")), // SDL truncates the editing text, too
+				start,
+				length,
+			};
+			push_raw_event(raw)
+		},
+		Event::FingerDown { timestamp, touch_id, finger_id, x, y, dx, dy, pressure } => {
+			push_raw_event(finger_event(SDL_EventType::SDL_FINGERDOWN, timestamp, touch_id, finger_id, (x, y, dx, dy), pressure))
+		},
+		Event::FingerMotion { timestamp, touch_id, finger_id, x, y, dx, dy, pressure } => {
+			push_raw_event(finger_event(SDL_EventType::SDL_FINGERMOTION, timestamp, touch_id, finger_id, (x, y, dx, dy), pressure))
+		},
+		Event::FingerUp { timestamp, touch_id, finger_id, x, y, dx, dy, pressure } => {
+			push_raw_event(finger_event(SDL_EventType::SDL_FINGERUP, timestamp, touch_id, finger_id, (x, y, dx, dy), pressure))
+		},
+		Event::MultiGesture { timestamp, touch_id, d_theta, d_dist, x, y, num_fingers } => {
+			let mut raw: SDL_Event = unsafe { mem::zeroed() };
+			raw.mgesture = SDL_MultiGestureEvent {
+				type_: SDL_EventType::SDL_MULTIGESTURE as u32,
+				timestamp,
+				touchId: touch_id,
+				dTheta: d_theta,
+				dDist: d_dist,
+				x, y,
+				numFingers: num_fingers,
+				padding: 0,
+			};
+			push_raw_event(raw)
+		},
+		Event::DropFile { timestamp, ref filename } => {
+			// the file name is freed with SDL_free() when the event is polled
+			let file = unsafe { sdl2_sys::SDL_malloc(filename.len() + 1) } as *mut c_char;
+			if file.is_null() {
+				return Err("Out of memory".to_string());
+			}
+			unsafe {
+				ptr::copy_nonoverlapping(filename.as_ptr() as *const c_char, file, filename.len());
+				*file.add(filename.len()) = 0;
+			}
+			let mut raw: SDL_Event = unsafe { mem::zeroed() };
+			raw.drop = SDL_DropEvent { type_: SDL_EventType::SDL_DROPFILE as u32, timestamp, file, windowID: 0 };
+			push_raw_event(raw).inspect_err( |_| { unsafe { sdl2_sys::SDL_free(file as *mut c_void) }; } )
+		},
+		_ => event_subsystem.push_event(event),
+	}
+}
+
+fn push_raw_event(mut raw: SDL_Event) -> Result<(), String> {
+	match unsafe { sdl2_sys::SDL_PushEvent(&mut raw) } {
+		1 => Ok(()),
+		0 => Err("The event was filtered.".to_string()),
+		_ => Err(sdl2::get_error()),
+	}
+}
+
+fn finger_event(type_: SDL_EventType, timestamp: u32, touch_id: i64, finger_id: i64, (x, y, dx, dy): (f32, f32, f32, f32), pressure: f32) -> SDL_Event {
+	let mut raw: SDL_Event = unsafe { mem::zeroed() };
+	raw.tfinger = SDL_TouchFingerEvent { type_: type_ as u32, timestamp, touchId: touch_id, fingerId: finger_id, x, y, dx, dy, pressure };
+	raw
+}
+
+/// splits the text into parts of at most max_len bytes without splitting UTF-8 characters
+fn split_text(text: &str, max_len: usize) -> impl Iterator<Item = &str> {
+	let mut rest = text;
+	std::iter::from_fn(move || {
+		if rest.is_empty() {
+			return None;
+		}
+		let mut end = rest.len().min(max_len);
+		while !rest.is_char_boundary(end) {
+			end -= 1;
+		}
+		let (part, remainder) = rest.split_at(end);
+		rest = remainder;
+		Some(part)
+	})
+}
+
+/// copies the text into a NUL-terminated buffer of a text event
+fn text_buffer(text: &str) -> [c_char; TEXT_SIZE] {
+	let mut buffer = [0; TEXT_SIZE];
+	for (dst, src) in buffer.iter_mut().zip(text.bytes().take(TEXT_SIZE - 1)) {
+		*dst = src as c_char;
+	}
+	buffer
+}
@@ -0,0 +1,263 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use sdl2::keyboard::{self, Keycode, Scancode, Mod};
+use sdl2::mouse::MouseButton;
+use sdl2::controller::{Axis, Button};
+
+use Touch;
+
+/// This struct collects the state of keyboard, mouse, touch screen and game controllers, which is updated by the events of each frame.
+///
+/// The "went down/up" sets contain all changes since the end of the previous frame, so even a key that was pressed and released within one frame is detected.
+pub struct InputState {
+	pub keys_down: HashSet<Keycode>,
+	pub scancodes_down: HashSet<Scancode>,
+	pub mouse_buttons_down: HashSet<MouseButton>,
+	pub keys_went_down: HashSet<Keycode>,
+	pub keys_went_up: HashSet<Keycode>,
+	pub scancodes_went_down: HashSet<Scancode>,
+	pub scancodes_went_up: HashSet<Scancode>,
+	pub mouse_buttons_went_down: HashSet<MouseButton>,
+	pub mouse_buttons_went_up: HashSet<MouseButton>,
+	pub touches: BTreeMap<(i64, i64), Touch>, // keyed by touch device and finger id
+	pub controllers: BTreeMap<i32, ControllerInput>,
+	pub controller_buttons_went_down: HashSet<Button>,
+	pub controller_buttons_went_up: HashSet<Button>,
+	pub controller_dead_zone: f32,
+	pub key_down_since: HashMap<Keycode, u32>, // in ms
+	pub mouse_pos: (i32, i32), // of the last handled mouse event
+	pub mouse_delta: (i32, i32),
+	pub mouse_wheel_delta: (f32, f32),
+	pub drag_start: (i32, i32),
+	pub pmouse: (i32, i32),
+}
+
+impl InputState {
+	pub fn new() -> Self {
+		InputState {
+			keys_down: HashSet::with_capacity(12),
+			scancodes_down: HashSet::with_capacity(12),
+			mouse_buttons_down: HashSet::with_capacity(5),
+			keys_went_down: HashSet::with_capacity(12),
+			keys_went_up: HashSet::with_capacity(12),
+			scancodes_went_down: HashSet::with_capacity(12),
+			scancodes_went_up: HashSet::with_capacity(12),
+			mouse_buttons_went_down: HashSet::with_capacity(5),
+			mouse_buttons_went_up: HashSet::with_capacity(5),
+			touches: BTreeMap::new(),
+			controllers: BTreeMap::new(),
+			controller_buttons_went_down: HashSet::with_capacity(8),
+			controller_buttons_went_up: HashSet::with_capacity(8),
+			controller_dead_zone: 0.1,
+			key_down_since: HashMap::with_capacity(12),
+			mouse_pos: (0, 0),
+			mouse_delta: (0, 0),
+			mouse_wheel_delta: (0.0, 0.0),
+			drag_start: (0, 0),
+			pmouse: (0, 0),
+		}
+	}
+
+	/// updates the state of the key, either code may be missing (e.g. SDL has no keycode for some keys of special keyboards)
+	pub fn key_down(&mut self, keycode: Option<Keycode>, scancode: Option<Scancode>, repeat: bool, now: u32) {
+		if let Some(code) = keycode {
+			if !repeat || !self.keys_down.contains(&code) {
+				self.keys_went_down.insert(code);
+				self.key_down_since.insert(code, now);
+			}
+			self.keys_down.insert(code);
+		}
+		if let Some(code) = scancode {
+			if !repeat || !self.scancodes_down.contains(&code) {
+				self.scancodes_went_down.insert(code);
+			}
+			self.scancodes_down.insert(code);
+		}
+	}
+
+	pub fn key_up(&mut self, keycode: Option<Keycode>, scancode: Option<Scancode>) {
+		if let Some(code) = keycode {
+			self.keys_down.remove(&code);
+			self.keys_went_up.insert(code);
+			self.key_down_since.remove(&code);
+		}
+		if let Some(code) = scancode {
+			self.scancodes_down.remove(&code);
+			self.scancodes_went_up.insert(code);
+		}
+	}
+
+	pub fn mouse_button_down(&mut self, button: MouseButton, x: i32, y: i32) {
+		if self.mouse_buttons_down.is_empty() {
+			self.drag_start = (x, y);
+		}
+		self.mouse_pos = (x, y);
+		self.mouse_buttons_down.insert(button);
+		self.mouse_buttons_went_down.insert(button);
+	}
+
+	pub fn mouse_button_up(&mut self, button: MouseButton, x: i32, y: i32) {
+		self.mouse_pos = (x, y);
+		self.mouse_buttons_down.remove(&button);
+		self.mouse_buttons_went_up.insert(button);
+	}
+
+	pub fn controller_added(&mut self, id: i32) {
+		self.controllers.entry(id).or_insert_with(ControllerInput::new);
+	}
+
+	pub fn controller_removed(&mut self, id: i32) {
+		if let Some(c) = self.controllers.remove(&id) {
+			self.controller_buttons_went_up.extend(c.buttons);
+		}
+	}
+
+	pub fn controller_button_down(&mut self, id: i32, button: Button) {
+		self.controllers.entry(id).or_insert_with(ControllerInput::new).buttons.insert(button);
+		self.controller_buttons_went_down.insert(button);
+	}
+
+	pub fn controller_button_up(&mut self, id: i32, button: Button) {
+		self.controllers.entry(id).or_insert_with(ControllerInput::new).buttons.remove(&button);
+		self.controller_buttons_went_up.insert(button);
+	}
+
+	pub fn controller_axis_moved(&mut self, id: i32, axis: Axis, value: i16) {
+		self.controllers.entry(id).or_insert_with(ControllerInput::new).axes.insert(axis, value);
+	}
+
+	/// checks if the button is pressed on the controller with the provided id
+	pub fn controller_button_is_down(&self, id: i32, button: Button) -> bool {
+		self.controllers.get(&id).is_some_and( |c| c.buttons.contains(&button) )
+	}
+
+	/// checks if the button is pressed on any controller
+	pub fn any_controller_button_is_down(&self, button: Button) -> bool {
+		self.controllers.values().any( |c| c.buttons.contains(&button) )
+	}
+
+	/// returns the value of the axis of the controller with the provided id in [-1.0, 1.0] with the dead zone applied
+	pub fn controller_axis(&self, id: i32, axis: Axis) -> f32 {
+		match self.controllers.get(&id).and_then( |c| c.axes.get(&axis) ) {
+			Some(value) => apply_dead_zone(*value, self.controller_dead_zone),
+			None => 0.0,
+		}
+	}
+
+	/// returns the value of the axis with the largest deflection of all controllers in [-1.0, 1.0] with the dead zone applied
+	pub fn any_controller_axis(&self, axis: Axis) -> f32 {
+		self.controllers.keys()
+			.map( |id| self.controller_axis(*id, axis) )
+			.fold(0.0, |a, b| if b.abs() > a.abs() { b } else { a })
+	}
+
+	pub fn mouse_moved(&mut self, x: i32, y: i32, xrel: i32, yrel: i32) {
+		self.mouse_pos = (x, y);
+		self.mouse_delta.0 += xrel;
+		self.mouse_delta.1 += yrel;
+	}
+
+	pub fn mouse_wheel(&mut self, dx: f32, dy: f32) {
+		self.mouse_wheel_delta.0 += dx;
+		self.mouse_wheel_delta.1 += dy;
+	}
+
+	/// resets the per-frame data and stores the mouse position of the finished frame
+	pub fn end_frame(&mut self, mouse_pos: (i32, i32)) {
+		self.keys_went_down.clear();
+		self.keys_went_up.clear();
+		self.scancodes_went_down.clear();
+		self.scancodes_went_up.clear();
+		self.controller_buttons_went_down.clear();
+		self.controller_buttons_went_up.clear();
+		self.mouse_buttons_went_down.clear();
+		self.mouse_buttons_went_up.clear();
+		self.mouse_delta = (0, 0);
+		self.mouse_wheel_delta = (0.0, 0.0);
+		self.pmouse = mouse_pos;
+	}
+
+	/// returns for how long the key has been held down in ms (0 if the key is not pressed)
+	pub fn key_held_duration(&self, keycode: Keycode, now: u32) -> u32 {
+		match self.key_down_since.get(&keycode) {
+			Some(since) => now.saturating_sub(*since),
+			None => 0,
+		}
+	}
+}
+
+
+/// This struct contains the state of the input created by Sketch::push_key() etc., which is needed to fill in the events like SDL does.
+pub struct SyntheticInput {
+	pub pending_mouse_pos: Option<(i32, i32)>, // of the last pushed MouseMotion until it is handled, needed for xrel and yrel of the next one
+	pub mouse_buttons: u32, // bit mask as returned by SDL_GetMouseState()
+	pub keymod: Mod,
+}
+
+impl SyntheticInput {
+	pub fn new() -> Self {
+		SyntheticInput {
+			pending_mouse_pos: None,
+			mouse_buttons: 0,
+			keymod: Mod::empty(),
+		}
+	}
+
+	/// updates the pressed modifier keys, SDL does this before sending the key event
+	pub fn key(&mut self, keycode: Keycode, down: bool) {
+		let modifier = match keycode {
+			Keycode::LShift => keyboard::LSHIFTMOD,
+			Keycode::RShift => keyboard::RSHIFTMOD,
+			Keycode::LCtrl  => keyboard::LCTRLMOD,
+			Keycode::RCtrl  => keyboard::RCTRLMOD,
+			Keycode::LAlt   => keyboard::LALTMOD,
+			Keycode::RAlt   => keyboard::RALTMOD,
+			Keycode::LGui   => keyboard::LGUIMOD,
+			Keycode::RGui   => keyboard::RGUIMOD,
+			_ => { return; }
+		};
+		if down {
+			self.keymod.insert(modifier);
+		} else {
+			self.keymod.remove(modifier);
+		}
+	}
+
+	/// updates the pressed mouse buttons
+	pub fn mouse_button(&mut self, button: MouseButton, down: bool) {
+		let mask = match button {
+			MouseButton::Unknown => 0,
+			_ => 1 << (button as u32 - 1), // SDL_BUTTON(X)
+		};
+		if down {
+			self.mouse_buttons |= mask;
+		} else {
+			self.mouse_buttons &= !mask;
+		}
+	}
+}
+
+
+/// This struct contains the state of a single game controller.
+pub struct ControllerInput {
+	pub buttons: HashSet<Button>,
+	pub axes: HashMap<Axis, i16>,
+}
+
+impl ControllerInput {
+	fn new() -> Self {
+		ControllerInput {
+			buttons: HashSet::with_capacity(8),
+			axes: HashMap::with_capacity(6),
+		}
+	}
+}
+
+/// converts a raw axis value to [-1.0, 1.0], values inside the dead zone are 0.0 and the remaining range is rescaled
+pub fn apply_dead_zone(value: i16, dead_zone: f32) -> f32 {
+	let v = (value as f32 / 32767.0).max(-1.0);
+	if v.abs() <= dead_zone {
+		0.0
+	} else {
+		v.signum() * (v.abs() - dead_zone) / (1.0 - dead_zone)
+	}
+}
@@ -1,63 +1,210 @@
 //! SDL2Sketch for Rust tries to simplify the use of [rust-sdl2](https://github.com/Rust-SDL2/rust-sdl2) by following the style of the [p5.js](https://p5js.org) API. It does not try to be a complete game engine, but just wants to make it as easy as possible to create visual applications in Rust without much boilerplate code. Code examples and the source code of SDL2Sketch can be found on its [GitHub page](https://github.com/emirpnet/sdl2sketch-rs).
 
+extern crate libc;
 extern crate num_traits;
+extern crate rand;
 extern crate sdl2;
 extern crate sdl2_sys;
 
-use std::{env, thread, time};
-use std::collections::HashSet;
+use std::{env, fs};
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
 use sdl2::render::{Canvas, TextureCreator};
 use sdl2::EventPump;
-use sdl2::event::Event;
-use sdl2::mouse::MouseState;
+use sdl2::mouse::{MouseState, MouseWheelDirection};
 use sdl2_sys::SDL_GetTicks;
 use sdl2::gfx::framerate::FPSManager;
 use sdl2::gfx::primitives::DrawRenderer;
 use sdl2::image::{Sdl2ImageContext, LoadSurface};
 use sdl2::surface::Surface;
+use sdl2::video::{FullscreenType, WindowPos};
+use sdl2::controller::GameController;
+use sdl2::haptic::Haptic;
+use rand::{Rng, SeedableRng, XorShiftRng};
+use rand::distributions::normal::StandardNormal;
+use rand::distributions::range::SampleRange;
 
 // re-exports
 #[doc(no_inline)] pub use sdl2::pixels::Color;
 #[doc(no_inline)] pub use sdl2::keyboard::Keycode;
+#[doc(no_inline)] pub use sdl2::keyboard::Scancode;
+#[doc(no_inline)] pub use sdl2::keyboard::Mod as KeyMod;
 #[doc(no_inline)] pub use sdl2::mouse::MouseButton;
+#[doc(no_inline)] pub use sdl2::controller::Axis as ControllerAxis;
+#[doc(no_inline)] pub use sdl2::controller::Button as ControllerButton;
+#[doc(no_inline)] pub use sdl2::event::{Event, WindowEvent};
 #[doc(no_inline)] pub use sdl2::render::Texture as Image;
 
 /// module containing utility functions
 pub mod utils;
+use utils::Noise;
+
+/// module containing the input state of a sketch
+mod input;
+use input::{InputState, SyntheticInput};
+
+/// module containing the binding of named actions to inputs
+mod actions;
+use actions::{ActionMap, QUIT_ACTION};
+pub use actions::{Binding, AxisBinding};
+
+/// module containing the clock of a sketch
+mod clock;
+use clock::Clock;
+
+/// module containing the local date and time
+mod localtime;
+
+/// module containing the conversion of events for the event queue of SDL
+mod events;
+
+/// module containing the recording and replay of events
+mod recording;
+use recording::{Recorder, Replay};
+
+/// module containing helper functions for headless snapshot tests of sketches
+pub mod testing;
+
+/// module containing a synthesizer for procedural audio
+pub mod synth;
+use synth::Synth;
+
+/// module containing the spectrum and level analysis of audio
+pub mod analyzer;
+
+/// module containing the playback of sounds and music (needs the feature "audio")
+#[cfg(feature = "audio")]
+pub mod audio;
+#[cfg(feature = "audio")]
+pub use audio::{Sound, Music};
 
 
 /// starts the sketch and runs the main loop
+///
+/// To drive the sketch from another loop or a test instead, see Sketch::step(). If the sketch was quit before, the main loop continues without calling setup() again.
 pub fn run<T: MainLoopMethods>(s: &mut Sketch, m: &mut T) {
-	m.setup(s);
+	if !s.started {
+		s.start(m);
+	}
 	s.running = true;
 	while s.running {
-		handle_events(s, m);
+		s.step(m);
+	}
+}
+
+/// subroutine of the main loop to call update() once per frame or at the fixed update rate
+///
+/// With a fixed update rate the elapsed time is accumulated and update() is called once for each full update step, but at most max_update_steps times per frame. If the sketch cannot keep up, the remaining time is dropped.
+fn handle_updates<T: MainLoopMethods>(s: &mut Sketch, m: &mut T) {
+	if s.update_step == 0.0 {
 		m.update(s);
-		m.draw(s);
-		s.present();
-		s.delay();
-		s.fps_data.update();
-		while s.no_loop && s.running {
-			thread::sleep(time::Duration::from_millis(250));
-			handle_events(s, m);
-		}
+		return;
+	}
+	s.update_accumulator += s.frame_duration;
+	let mut steps = 0;
+	while s.update_accumulator >= s.update_step && steps < s.max_update_steps && s.running {
+		m.update(s);
+		s.end_frame();
+		s.update_accumulator -= s.update_step;
+		steps += 1;
+	}
+	if s.update_accumulator >= s.update_step {
+		s.update_accumulator %= s.update_step;
 	}
 }
 
 /// subroutine of the main loop to handle events
 fn handle_events<T: MainLoopMethods>(s: &mut Sketch, m: &mut T) {
-	while let Some(event) = s.event_pump.poll_event() {
+	s.inject_replay_events();
+	while let Some(event) = s.next_event() {
+		if !s.touch_mouse_events && is_touch_mouse_event(&event) {
+			continue;
+		}
+		s.record_event(&event);
 		match event {
-			Event::Quit { .. }                             => { s.quit(); }
-			Event::KeyDown { keycode: Some(code), .. }     => { s.keys_down.insert(code); m.key_pressed(s, code); },
-			Event::KeyUp { keycode: Some(code), .. }       => { s.keys_down.remove(&code); m.key_released(s, code); },
+			Event::Quit { .. } if m.close_requested(s)     => { s.quit(); },
+			Event::Window { win_event, .. }                => { handle_window_event(s, m, win_event); },
+			Event::KeyDown { .. }                          => { handle_key_down(s, m, event); },
+			Event::KeyUp { .. }                            => { handle_key_up(s, m, event); },
+			Event::TextInput { text, .. }                  => { m.key_typed(s, &text); },
+			Event::TextEditing { text, start, length, .. } => { m.text_editing(s, &text, start, length); },
 			Event::MouseMotion { .. }                      => { handle_mouse_moved(s, m, event); },
-			Event::MouseButtonDown { mouse_btn, x, y, .. } => { m.mouse_pressed(s, mouse_btn, x, y); },
-			Event::MouseButtonUp { mouse_btn, x, y, .. }   => { m.mouse_released(s, mouse_btn, x, y); },
+			Event::MouseButtonDown { .. }                  => { handle_mouse_button_down(s, m, event); },
+			Event::MouseButtonUp { .. }                    => { handle_mouse_button_up(s, m, event); },
+			Event::MouseWheel { .. }                       => { handle_mouse_wheel(s, m, event); },
+			Event::DropFile { filename, .. }               => { m.file_dropped(s, Path::new(&filename)); },
+			Event::FingerDown { .. }                       => { handle_touch_event(s, m, event); },
+			Event::FingerMotion { .. }                     => { handle_touch_event(s, m, event); },
+			Event::FingerUp { .. }                         => { handle_touch_event(s, m, event); },
+			Event::MultiGesture { .. }                     => { handle_touch_event(s, m, event); },
+			Event::ControllerDeviceAdded { .. }            => { handle_controller_event(s, m, event); },
+			Event::ControllerDeviceRemoved { .. }          => { handle_controller_event(s, m, event); },
+			Event::ControllerButtonDown { .. }             => { handle_controller_event(s, m, event); },
+			Event::ControllerButtonUp { .. }               => { handle_controller_event(s, m, event); },
+			Event::ControllerAxisMotion { .. }             => { handle_controller_event(s, m, event); },
 			_ => {}
 		}
 	}
+	s.synthetic_input.pending_mouse_pos = None; // all pushed events are handled, the input state is up to date
+	if let Some(ref mut recorder) = s.recorder {
+		recorder.flush();
+	}
+	if s.action_went_down(QUIT_ACTION) {
+		s.quit();
+	}
+}
+
+/// subroutine of the handle_events, i.e. sub-subroutine of the main loop
+fn handle_key_down<T: MainLoopMethods>(s: &mut Sketch, m: &mut T, event: Event) {
+	let (keycode, scancode, keymod, repeat) = match event {
+		Event::KeyDown { keycode, scancode, keymod, repeat, .. } => (keycode, scancode, keymod, repeat),
+		_ => { return; }
+	};
+
+	let now = s.clock.now();
+	s.input.key_down(keycode, scancode, repeat, now);
+	if let Some(keycode) = keycode { // keys without keycode are only tracked by their scancode
+		m.key_pressed(s, KeyEvent { keycode, scancode, keymod, repeat });
+	}
+}
+
+/// subroutine of the handle_events, i.e. sub-subroutine of the main loop
+fn handle_key_up<T: MainLoopMethods>(s: &mut Sketch, m: &mut T, event: Event) {
+	let (keycode, scancode, keymod, repeat) = match event {
+		Event::KeyUp { keycode, scancode, keymod, repeat, .. } => (keycode, scancode, keymod, repeat),
+		_ => { return; }
+	};
+
+	s.input.key_up(keycode, scancode);
+	if let Some(keycode) = keycode {
+		m.key_released(s, KeyEvent { keycode, scancode, keymod, repeat });
+	}
+}
+
+/// subroutine of the handle_events, i.e. sub-subroutine of the main loop
+fn handle_window_event<T: MainLoopMethods>(s: &mut Sketch, m: &mut T, win_event: WindowEvent) {
+	match win_event {
+		WindowEvent::SizeChanged(w, h) => {
+			if s.logical_size.is_none() {
+				s.width = w as u32;
+				s.height = h as u32;
+			}
+			m.window_resized(s, w, h);
+		},
+		WindowEvent::FocusGained => {
+			s.set_focused(true);
+			m.window_focused(s);
+		},
+		WindowEvent::FocusLost => {
+			s.set_focused(false);
+			m.window_unfocused(s);
+		},
+		WindowEvent::Enter     => { m.mouse_entered(s); },
+		WindowEvent::Leave     => { m.mouse_left(s); },
+		WindowEvent::Minimized => { m.window_minimized(s); },
+		WindowEvent::Restored  => { m.window_restored(s); },
+		_ => {}
+	}
 }
 
 /// subroutine of the handle_events, i.e. sub-subroutine of the main loop
@@ -67,13 +214,135 @@ fn handle_mouse_moved<T: MainLoopMethods>(s: &mut Sketch, m: &mut T, event: Even
 		_ => { return; }
 	};
 
+	s.input.mouse_moved(x, y, xrel, yrel);
 	if mstate.pressed_mouse_buttons().count() > 0 {
-		m.mouse_dragged(s, x, y, xrel, yrel);
+		let drag = MouseDrag {
+			buttons: mstate.pressed_mouse_buttons().collect(),
+			start_x: s.input.drag_start.0,
+			start_y: s.input.drag_start.1,
+		};
+		m.mouse_dragged(s, &drag, x, y, xrel, yrel);
 	} else {
 		m.mouse_moved(s, x, y, xrel, yrel);
 	}
 }
 
+/// subroutine of the handle_events, i.e. sub-subroutine of the main loop
+fn handle_mouse_button_down<T: MainLoopMethods>(s: &mut Sketch, m: &mut T, event: Event) {
+	let (button, x, y) = match event {
+		Event::MouseButtonDown { mouse_btn, x, y, .. } => (mouse_btn, x, y),
+		_ => { return; }
+	};
+
+	s.input.mouse_button_down(button, x, y);
+	m.mouse_pressed(s, button, x, y);
+}
+
+/// subroutine of the handle_events, i.e. sub-subroutine of the main loop
+fn handle_mouse_button_up<T: MainLoopMethods>(s: &mut Sketch, m: &mut T, event: Event) {
+	let (button, clicks, x, y) = match event {
+		Event::MouseButtonUp { mouse_btn, clicks, x, y, .. } => (mouse_btn, clicks, x, y),
+		_ => { return; }
+	};
+
+	s.input.mouse_button_up(button, x, y);
+	m.mouse_released(s, button, x, y);
+	m.mouse_clicked(s, button, x, y);
+	if clicks == 2 {
+		m.double_clicked(s, button, x, y);
+	}
+}
+
+/// subroutine of the handle_events, i.e. sub-subroutine of the main loop
+fn handle_mouse_wheel<T: MainLoopMethods>(s: &mut Sketch, m: &mut T, event: Event) {
+	let (x, y, direction) = match event {
+		Event::MouseWheel { x, y, direction, .. } => (x, y, direction),
+		_ => { return; }
+	};
+
+	// SDL2 0.31 only provides integer deltas, precise (fractional) deltas are passed on as soon as they are available
+	let (mut dx, mut dy) = (x as f32, y as f32);
+	if direction == MouseWheelDirection::Flipped {
+		dx = -dx;
+		dy = -dy;
+	}
+	s.input.mouse_wheel(dx, dy);
+	m.mouse_wheel(s, dx, dy);
+}
+
+/// subroutine of the handle_events, i.e. sub-subroutine of the main loop
+fn handle_touch_event<T: MainLoopMethods>(s: &mut Sketch, m: &mut T, event: Event) {
+	match event {
+		Event::FingerDown { touch_id, finger_id, x, y, dx, dy, pressure, .. } => {
+			let touch = s.touch_point(touch_id, finger_id, x, y, dx, dy, pressure);
+			s.input.touches.insert((touch_id, finger_id), touch);
+			m.touch_started(s, &touch);
+		},
+		Event::FingerMotion { touch_id, finger_id, x, y, dx, dy, pressure, .. } => {
+			let touch = s.touch_point(touch_id, finger_id, x, y, dx, dy, pressure);
+			s.input.touches.insert((touch_id, finger_id), touch);
+			m.touch_moved(s, &touch);
+		},
+		Event::FingerUp { touch_id, finger_id, x, y, dx, dy, pressure, .. } => {
+			let touch = s.touch_point(touch_id, finger_id, x, y, dx, dy, pressure);
+			s.input.touches.remove(&(touch_id, finger_id));
+			m.touch_ended(s, &touch);
+		},
+		Event::MultiGesture { d_theta, d_dist, x, y, .. } => {
+			let (cx, cy) = s.touch_to_canvas(x, y);
+			if d_dist != 0.0 {
+				m.touch_pinched(s, d_dist, cx, cy);
+			}
+			if d_theta != 0.0 {
+				m.touch_rotated(s, d_theta, cx, cy);
+			}
+		},
+		_ => {}
+	}
+}
+
+/// checks if the event is a mouse event which SDL generated from a touch event
+fn is_touch_mouse_event(event: &Event) -> bool {
+	const TOUCH_MOUSE_ID: u32 = u32::MAX; // SDL_TOUCH_MOUSEID
+	match *event {
+		Event::MouseMotion { which, .. }     => which == TOUCH_MOUSE_ID,
+		Event::MouseButtonDown { which, .. } => which == TOUCH_MOUSE_ID,
+		Event::MouseButtonUp { which, .. }   => which == TOUCH_MOUSE_ID,
+		Event::MouseWheel { which, .. }      => which == TOUCH_MOUSE_ID,
+		_ => false,
+	}
+}
+
+/// subroutine of the handle_events, i.e. sub-subroutine of the main loop
+fn handle_controller_event<T: MainLoopMethods>(s: &mut Sketch, m: &mut T, event: Event) {
+	match event {
+		Event::ControllerDeviceAdded { which, .. } => {
+			let id = s.open_controller(which);
+			s.input.controller_added(id);
+			m.controller_connected(s, id);
+		},
+		Event::ControllerDeviceRemoved { which, .. } => {
+			s.controller_devices.remove(&which);
+			s.input.controller_removed(which);
+			m.controller_disconnected(s, which);
+		},
+		Event::ControllerButtonDown { which, button, .. } => {
+			s.input.controller_button_down(which, button);
+			m.controller_button_pressed(s, which, button);
+		},
+		Event::ControllerButtonUp { which, button, .. } => {
+			s.input.controller_button_up(which, button);
+			m.controller_button_released(s, which, button);
+		},
+		Event::ControllerAxisMotion { which, axis, value, .. } => {
+			s.input.controller_axis_moved(which, axis, value);
+			let value = s.input.controller_axis(which, axis);
+			m.controller_axis_moved(s, which, axis, value);
+		},
+		_ => {}
+	}
+}
+
 
 /// This trait must be implemented by the state struct of the application and provided to run().
 pub trait MainLoopMethods {
@@ -89,39 +358,136 @@ pub trait MainLoopMethods {
 
 	/// called inside the main loop on a KeyDown event
 	///
-	/// The default implementation quits the main loop when Escape is pressed.
-	fn key_pressed(&mut self, s: &mut Sketch, code: Keycode) {
-		match code {
-			Keycode::Escape => { s.quit(); },
-			_ => {}
-		}
-	}
+	/// Quitting the main loop with Escape is not handled here, but by the default binding of the action "quit" (see Sketch::bind_action()).
+	fn key_pressed(&mut self, _s: &mut Sketch, _key: KeyEvent) {}
 
 	/// called inside the main loop on a KeyUp event
-	fn key_released(&mut self, _s: &mut Sketch, _key: Keycode) {}
+	fn key_released(&mut self, _s: &mut Sketch, _key: KeyEvent) {}
+
+	/// called inside the main loop on a TextInput event, i.e. when text was entered (respects keyboard layout, modifiers and IME)
+	///
+	/// The text is usually one character, but can be longer when entered via an input method editor (IME).
+	fn key_typed(&mut self, _s: &mut Sketch, _text: &str) {}
+
+	/// called inside the main loop on a TextEditing event, i.e. while a text is composed in an input method editor (IME)
+	///
+	/// The parameters are the current composition text, the cursor position and the length of the selection inside the composition. The final text is provided to key_typed().
+	fn text_editing(&mut self, _s: &mut Sketch, _text: &str, _start: i32, _length: i32) {}
 
 	/// called inside the main loop on a MouseMotion event, if no MouseButton is pressed
 	fn mouse_moved(&mut self, _s: &mut Sketch, _x: i32, _y: i32, _xrel: i32, _yrel: i32) {}
 
 	/// called inside the main loop on a MouseMotion event, if a MouseButton is pressed
 	///
-	/// ignores which button(s) are pressed (TODO)
-	fn mouse_dragged(&mut self, _s: &mut Sketch, _x: i32, _y: i32, _xrel: i32, _yrel: i32) {}
+	/// The pressed buttons and the start position of the drag are provided in drag.
+	fn mouse_dragged(&mut self, _s: &mut Sketch, _drag: &MouseDrag, _x: i32, _y: i32, _xrel: i32, _yrel: i32) {}
 
 	/// called inside the main loop on a MouseButtonDown event
 	fn mouse_pressed(&mut self, _s: &mut Sketch, _button: MouseButton, _x: i32, _y: i32) {}
 
 	/// called inside the main loop on a MouseButtonUp event
 	fn mouse_released(&mut self, _s: &mut Sketch, _button: MouseButton, _x: i32, _y: i32) {}
+
+	/// called inside the main loop on a MouseButtonUp event after mouse_released()
+	fn mouse_clicked(&mut self, _s: &mut Sketch, _button: MouseButton, _x: i32, _y: i32) {}
+
+	/// called inside the main loop after mouse_clicked() if the click was the second one of a double click
+	fn double_clicked(&mut self, _s: &mut Sketch, _button: MouseButton, _x: i32, _y: i32) {}
+
+	/// called inside the main loop on a MouseWheel event
+	///
+	/// Positive dy means scrolling up (away from the user), positive dx scrolling to the right. A flipped ("natural") scroll direction of the system is already compensated.
+	/// The deltas are always whole numbers, because sdl2 0.31 does not provide the precise (fractional) deltas of touchpads and high-resolution wheels.
+	fn mouse_wheel(&mut self, _s: &mut Sketch, _dx: f32, _dy: f32) {}
+
+	/// called inside the main loop when the window gained the keyboard focus
+	fn window_focused(&mut self, _s: &mut Sketch) {}
+
+	/// called inside the main loop when the window lost the keyboard focus
+	fn window_unfocused(&mut self, _s: &mut Sketch) {}
+
+	/// called inside the main loop when the mouse pointer entered the window
+	fn mouse_entered(&mut self, _s: &mut Sketch) {}
+
+	/// called inside the main loop when the mouse pointer left the window
+	fn mouse_left(&mut self, _s: &mut Sketch) {}
+
+	/// called inside the main loop when the window was minimized
+	///
+	/// To pause the sketch while it is minimized, call no_loop(true) here and no_loop(false) in window_restored().
+	fn window_minimized(&mut self, _s: &mut Sketch) {}
+
+	/// called inside the main loop when the window was restored after being minimized or maximized
+	fn window_restored(&mut self, _s: &mut Sketch) {}
+
+	/// called inside the main loop on a Quit event, e.g. when the close button of the window was clicked
+	///
+	/// The main loop is only quit if this returns true (default), so returning false cancels closing the window.
+	fn close_requested(&mut self, _s: &mut Sketch) -> bool {
+		true
+	}
+
+	/// called inside the main loop on a DropFile event, i.e. when a file was dragged and dropped onto the window
+	///
+	/// If several files are dropped at once, this is called for each of them.
+	/// Dropped text (SDL's DropText event) is not supported, because sdl2 0.31 does not provide this event.
+	fn file_dropped(&mut self, _s: &mut Sketch, _path: &Path) {}
+
+	/// called inside the main loop on a FingerDown event, i.e. when a finger touches the screen
+	fn touch_started(&mut self, _s: &mut Sketch, _touch: &Touch) {}
+
+	/// called inside the main loop on a FingerMotion event
+	fn touch_moved(&mut self, _s: &mut Sketch, _touch: &Touch) {}
+
+	/// called inside the main loop on a FingerUp event, i.e. when a finger is lifted from the screen
+	fn touch_ended(&mut self, _s: &mut Sketch, _touch: &Touch) {}
+
+	/// called inside the main loop when two or more fingers are moved towards (d_dist < 0) or away from (d_dist > 0) each other
+	///
+	/// (x, y) is the center of the fingers.
+	fn touch_pinched(&mut self, _s: &mut Sketch, _d_dist: f32, _x: i32, _y: i32) {}
+
+	/// called inside the main loop when two or more fingers are rotated, d_theta is the rotation in radians
+	///
+	/// (x, y) is the center of the fingers.
+	fn touch_rotated(&mut self, _s: &mut Sketch, _d_theta: f32, _x: i32, _y: i32) {}
+
+	/// called inside the main loop when a game controller was connected
+	///
+	/// The id identifies the controller in all other controller callbacks and functions. Controllers connected at startup are reported, too.
+	fn controller_connected(&mut self, _s: &mut Sketch, _id: i32) {}
+
+	/// called inside the main loop when a game controller was disconnected
+	fn controller_disconnected(&mut self, _s: &mut Sketch, _id: i32) {}
+
+	/// called inside the main loop on a ControllerButtonDown event
+	fn controller_button_pressed(&mut self, _s: &mut Sketch, _id: i32, _button: ControllerButton) {}
+
+	/// called inside the main loop on a ControllerButtonUp event
+	fn controller_button_released(&mut self, _s: &mut Sketch, _id: i32, _button: ControllerButton) {}
+
+	/// called inside the main loop on a ControllerAxisMotion event
+	///
+	/// The value is between -1.0 and 1.0 (triggers: 0.0 to 1.0) with the dead zone applied (see Sketch::controller_dead_zone()).
+	fn controller_axis_moved(&mut self, _s: &mut Sketch, _id: i32, _axis: ControllerAxis, _value: f32) {}
+
+	/// called inside the main loop when the size of the window has changed
+	///
+	/// The parameters are the new window size in pixels. If a logical size is set (see Sketch::logical_size()), width() and height() of the sketch do not change.
+	fn window_resized(&mut self, _s: &mut Sketch, _w: i32, _h: i32) {}
 }
 
 
 /// This struct contains the necessary SDL2 subsystem objects and provides most of the API.
 pub struct Sketch {
 	running: bool,
+	started: bool,
 	no_loop: bool,
+	redraw: bool,
 	width: u32,
 	height: u32,
+	logical_size: Option<(u32, u32)>,
+	scale_quality_hint: Option<String>, // value of the hint before pixel_art()
 	fill_color: Option<Color>,
 	stroke_color: Option<Color>,
 	stroke_weight: u8,
@@ -132,10 +498,40 @@ pub struct Sketch {
 	canvas: Canvas<sdl2::video::Window>,
 	event_pump: EventPump,
 	_image_context: Sdl2ImageContext,
+	controller_subsystem: Option<sdl2::GameControllerSubsystem>,
+	haptic_subsystem: Option<sdl2::HapticSubsystem>,
+	controller_devices: HashMap<i32, (GameController, Option<Haptic>)>,
+	event_subsystem: sdl2::EventSubsystem,
+	replay_events: VecDeque<Event>,
+	synthetic_input: SyntheticInput,
+	waited_event: Option<Event>,
+	touch_mouse_events: bool,
+	focused: bool,
+	unfocused_framerate: u32,
+	focused_framerate: u32, // framerate set by set_framerate()
+	frame_count: u64,
+	clock: Clock,
+	idle: bool,
+	recorder: Option<Recorder>,
+	replay: Option<Replay>,
+	seed: u64,
+	rng: XorShiftRng,
+	noise: Noise,
+	last_frame_time: f64, // in ms
+	frame_duration: f64, // in ms, exact duration of the last frame
+	delta_time: u32, // in ms, rounded frame_duration
+	update_step: f64, // in ms, 0.0 = update() once per frame
+	update_accumulator: f64, // in ms
+	max_update_steps: u32,
+	#[cfg(feature = "audio")]
+	audio: audio::AudioState,
+	synth_device: Option<sdl2::audio::AudioDevice<Synth>>,
+	_sdl_context: sdl2::Sdl,
 	texture_creator: TextureCreator<sdl2::video::WindowContext>,
 	fps_manager: FPSManager,
 	fps_data: FPSData,
-	keys_down: HashSet<Keycode>,
+	input: InputState,
+	actions: ActionMap,
 }
 
 
@@ -144,14 +540,24 @@ impl Sketch {
 	/* general methods */
 
 	/// create a new sketch
+	///
+	/// If the environment variable SDL2SKETCH_FULLSCREEN is set to a display index, the sketch starts in fullscreen mode on that display.
+	/// If the environment variable SDL2SKETCH_VIRTUAL_CLOCK is set, the sketch uses a virtual clock (see clock_mode()).
+	/// The environment variables SDL2SKETCH_RECORD and SDL2SKETCH_REPLAY can be set to a file name to record or replay the input (see record_input() and replay_input()).
 	pub fn new(width: u32, height: u32, title: &str) -> Self {
-		let (canvas, event_pump, image_context) = init_sdl_subsystems(width, height, title);
+		let (sdl_context, canvas, event_pump, image_context) = init_sdl_subsystems(width, height, title);
+		let seed = rand::random();
 		let texture_creator = canvas.texture_creator();
-		Sketch {
+		let fps_manager = FPSManager::new();
+		let mut sketch = Sketch {
 			running: false,
+			started: false,
 			no_loop: false,
+			redraw: false,
 			width,
 			height,
+			logical_size: None,
+			scale_quality_hint: None,
 			fill_color: Some(Color::RGB(255, 255, 255)),
 			stroke_color: Some(Color::RGB(255, 255, 255)),
 			stroke_weight: 1,
@@ -162,10 +568,118 @@ impl Sketch {
 			canvas,
 			event_pump,
 			_image_context: image_context,
+			controller_subsystem: sdl_context.game_controller().map_err( |e| { eprintln!("Initialization of SDL2 game controller subsystem failed. {}", e); } ).ok(),
+			haptic_subsystem: sdl_context.haptic().map_err( |e| { eprintln!("Initialization of SDL2 haptic subsystem failed. {}", e); } ).ok(),
+			controller_devices: HashMap::new(),
+			event_subsystem: sdl_context.event().expect("Initialization of SDL2 event subsystem failed. Abort."),
+			replay_events: VecDeque::new(),
+			synthetic_input: SyntheticInput::new(),
+			waited_event: None,
+			touch_mouse_events: true,
+			focused: true,
+			unfocused_framerate: 0,
+			focused_framerate: fps_manager.get_framerate() as u32,
+			frame_count: 0,
+			clock: Clock::new(),
+			idle: false,
+			recorder: None,
+			replay: None,
+			seed,
+			rng: rng_from_seed(seed),
+			noise: Noise::new(seed as u32),
+			last_frame_time: 0.0,
+			frame_duration: 0.0,
+			delta_time: 0,
+			update_step: 0.0,
+			update_accumulator: 0.0,
+			max_update_steps: 5,
+			#[cfg(feature = "audio")]
+			audio: audio::AudioState::new(&sdl_context),
+			synth_device: None,
+			_sdl_context: sdl_context,
 			texture_creator,
-			fps_manager: FPSManager::new(),
+			fps_manager,
 			fps_data: FPSData::new(1000), // parameter sets update interval in ms
-			keys_down: HashSet::with_capacity(12),
+			input: InputState::new(),
+			actions: ActionMap::new(),
+		};
+		if let Ok(index) = env::var("SDL2SKETCH_FULLSCREEN") {
+			sketch.fullscreen_on_display(index.parse().unwrap_or(0));
+		}
+		if env::var("SDL2SKETCH_VIRTUAL_CLOCK").is_ok() {
+			sketch.clock_mode(ClockMode::VIRTUAL);
+		}
+		if let Ok(path) = env::var("SDL2SKETCH_REPLAY") {
+			sketch.replay_input(Path::new(&path)).unwrap_or_else( |e| { eprintln!("{}", e); } );
+		}
+		if let Ok(path) = env::var("SDL2SKETCH_RECORD") {
+			sketch.record_input(Path::new(&path)).unwrap_or_else( |e| { eprintln!("{}", e); } );
+		}
+		sketch
+	}
+
+	/// calls setup() and prepares the main loop
+	fn start<T: MainLoopMethods>(&mut self, m: &mut T) {
+		self.clock.restart();
+		self.fps_data.restart();
+		m.setup(self);
+		self.started = true;
+		self.running = true;
+		self.redraw = true; // the first frame is always drawn, even if setup() calls no_loop(true)
+		self.last_frame_time = self.clock.now_exact();
+	}
+
+	/// runs a single frame of the main loop, i.e. handles the events, calls update() and draw(), presents the canvas and delays to keep the framerate
+	///
+	/// This allows to embed the sketch into another loop or to advance it frame by frame in a test, e.g. while s.step(&mut state) {}. setup() is called by the first step. If the main loop is stopped by no_loop(true), each step waits up to 250 ms for events and handles them until redraw() is called.
+	/// Returns false if the sketch was quit. Once the sketch was quit, step() does nothing (see run() to continue the main loop).
+	pub fn step<T: MainLoopMethods>(&mut self, m: &mut T) -> bool {
+		if !self.started {
+			self.start(m);
+		} else if !self.running {
+			return false;
+		}
+		if self.no_loop && !self.redraw {
+			self.idle = true;
+			self.wait_event(250);
+			handle_events(self, m);
+			self.idle = false;
+			return self.running;
+		}
+		self.redraw = false;
+		self.begin_frame();
+		handle_events(self, m);
+		handle_updates(self, m);
+		m.draw(self);
+		self.present();
+		if self.update_step == 0.0 {
+			self.end_frame();
+		}
+		self.delay();
+		let now = self.clock.now();
+		self.fps_data.update(now);
+		self.running
+	}
+
+	/// runs n frames of the main loop (see step()), or less if the sketch is quit
+	///
+	/// While the main loop is stopped by no_loop(true), each of the n steps only waits up to 250 ms for events instead of running a frame.
+	pub fn run_frames<T: MainLoopMethods>(&mut self, m: &mut T, n: u32) {
+		for _ in 0..n {
+			if !self.step(m) {
+				break;
+			}
+		}
+	}
+
+	/// runs frames of the main loop (see step()) until the predicate returns true or the sketch is quit
+	///
+	/// The predicate is checked before each frame and gets the sketch and the state of the sketch, e.g. s.run_until(&mut state, |s, _| s.frame_count() == 10).
+	pub fn run_until<T, F>(&mut self, m: &mut T, mut predicate: F) where T: MainLoopMethods, F: FnMut(&Sketch, &T) -> bool {
+		while !predicate(self, m) {
+			if !self.step(m) {
+				break;
+			}
 		}
 	}
 
@@ -179,6 +693,162 @@ impl Sketch {
 		self.height as i32
 	}
 
+	/// resizes the window of the sketch to the provided size in pixels
+	///
+	/// If a logical size is set, the canvas keeps its logical resolution and is scaled to the new window size.
+	pub fn resize_canvas(&mut self, width: u32, height: u32) {
+		self.canvas.window_mut().set_size(width, height).unwrap_or_else( |e| { eprintln!("SDL2 set_size() failed. {}", e); } );
+		if self.logical_size.is_none() {
+			self.width = width;
+			self.height = height;
+		}
+	}
+
+	/// allows or forbids resizing the window by dragging its edges
+	///
+	/// Not resizable by default.
+	pub fn resizable(&mut self, setting: bool) {
+		let sdl_bool = if setting { sdl2_sys::SDL_bool::SDL_TRUE } else { sdl2_sys::SDL_bool::SDL_FALSE };
+		unsafe { sdl2_sys::SDL_SetWindowResizable(self.canvas.window().raw(), sdl_bool); }
+	}
+
+	/// sets a fixed logical resolution of the canvas independent of the window size
+	///
+	/// The canvas is scaled to the window keeping its aspect ratio, the remaining area is letterboxed. After calling this function width() and height() return the logical size.
+	pub fn logical_size(&mut self, width: u32, height: u32) {
+		match self.canvas.set_logical_size(width, height) {
+			Ok(_) => {
+				self.logical_size = Some((width, height));
+				self.width = width;
+				self.height = height;
+			},
+			Err(e) => { eprintln!("SDL2 set_logical_size() failed. {}", e); },
+		}
+	}
+
+	/// removes a logical resolution set by logical_size(), i.e. the canvas has the size of the window again
+	pub fn no_logical_size(&mut self) {
+		unsafe { sdl2_sys::SDL_RenderSetLogicalSize(self.canvas.raw(), 0, 0); }
+		self.logical_size = None;
+		self.update_size_from_window();
+	}
+
+	/// switches the window to fullscreen mode on its current display or back to windowed mode
+	///
+	/// The fullscreen mode uses the desktop resolution of the display. If no logical size is set, width() and height() follow the new window size.
+	pub fn fullscreen(&mut self, setting: bool) {
+		let fullscreen_type = if setting { FullscreenType::Desktop } else { FullscreenType::Off };
+		self.canvas.window_mut().set_fullscreen(fullscreen_type).unwrap_or_else( |e| { eprintln!("SDL2 set_fullscreen() failed. {}", e); } );
+		self.update_size_from_window();
+	}
+
+	/// switches the window to fullscreen mode on the display with the provided index (see displays())
+	pub fn fullscreen_on_display(&mut self, index: i32) {
+		let bounds = match self.canvas.window().subsystem().display_bounds(index) {
+			Ok(b) => b,
+			Err(e) => { eprintln!("SDL2 display_bounds() failed for display {}. {}", index, e); return; }
+		};
+		self.canvas.window_mut().set_fullscreen(FullscreenType::Off).unwrap_or_else( |e| { eprintln!("SDL2 set_fullscreen() failed. {}", e); } );
+		self.canvas.window_mut().set_position(WindowPos::Positioned(bounds.x()), WindowPos::Positioned(bounds.y()));
+		self.fullscreen(true);
+	}
+
+	/// checks if the window is currently in fullscreen mode
+	pub fn is_fullscreen(&self) -> bool {
+		self.canvas.window().fullscreen_state() != FullscreenType::Off
+	}
+
+	/// returns the width of the display the window is on in pixels
+	pub fn display_width(&self) -> i32 {
+		self.current_display_mode().map_or(0, |m| m.w)
+	}
+
+	/// returns the height of the display the window is on in pixels
+	pub fn display_height(&self) -> i32 {
+		self.current_display_mode().map_or(0, |m| m.h)
+	}
+
+	/// returns the refresh rate of the display the window is on in Hz (0 if unknown)
+	pub fn display_refresh_rate(&self) -> i32 {
+		self.current_display_mode().map_or(0, |m| m.refresh_rate)
+	}
+
+	/// returns information about all available displays
+	///
+	/// The index of a DisplayInfo can be passed to fullscreen_on_display().
+	pub fn displays(&self) -> Vec<DisplayInfo> {
+		let video = self.canvas.window().subsystem();
+		let num_displays = video.num_video_displays().unwrap_or_else( |e| { eprintln!("SDL2 num_video_displays() failed. {}", e); 0 } );
+		let mut displays = Vec::with_capacity(num_displays as usize);
+		for index in 0..num_displays {
+			let (bounds, mode) = match (video.display_bounds(index), video.desktop_display_mode(index)) {
+				(Ok(b), Ok(m)) => (b, m),
+				_ => { continue; }
+			};
+			displays.push(DisplayInfo {
+				index,
+				name: video.display_name(index).unwrap_or_default(),
+				x: bounds.x(),
+				y: bounds.y(),
+				width: mode.w,
+				height: mode.h,
+				refresh_rate: mode.refresh_rate,
+			});
+		}
+		displays
+	}
+
+	/// returns the desktop display mode of the display the window is on
+	fn current_display_mode(&self) -> Option<sdl2::video::DisplayMode> {
+		let window = self.canvas.window();
+		window.display_index()
+			.and_then( |index| window.subsystem().desktop_display_mode(index) )
+			.map_err( |e| { eprintln!("SDL2 desktop_display_mode() failed. {}", e); } )
+			.ok()
+	}
+
+	/// sets width and height to the current window size (unless a logical size is set)
+	fn update_size_from_window(&mut self) {
+		if self.logical_size.is_none() {
+			let (w, h) = self.canvas.window().size();
+			self.width = w;
+			self.height = h;
+		}
+	}
+
+	/// enables the pixel-art mode: the sketch is drawn at the provided low resolution and scaled to the window by integer factors
+	///
+	/// The scaling uses nearest-neighbor filtering for images loaded afterwards, the remaining area of the window is letterboxed. Anti-aliasing is switched off (see no_smooth()).
+	/// Like with logical_size(), width(), height(), mouse_pos() and the mouse callbacks use the low resolution coordinates.
+	pub fn pixel_art(&mut self, width: u32, height: u32) {
+		if self.scale_quality_hint.is_none() {
+			self.scale_quality_hint = Some(sdl2::hint::get("SDL_RENDER_SCALE_QUALITY").unwrap_or_else( || "nearest".to_string() ));
+		}
+		sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "nearest");
+		self.logical_size(width, height);
+		self.set_integer_scale(true);
+		self.no_smooth();
+	}
+
+	/// disables the pixel-art mode, i.e. the canvas has the size of the window again
+	///
+	/// The filtering of images loaded afterwards is reset to the one used before pixel_art().
+	pub fn no_pixel_art(&mut self) {
+		if let Some(hint) = self.scale_quality_hint.take() {
+			sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", &hint);
+		}
+		self.set_integer_scale(false);
+		self.no_logical_size();
+	}
+
+	/// restricts the scaling of a logical size to integer factors
+	fn set_integer_scale(&mut self, setting: bool) {
+		let sdl_bool = if setting { sdl2_sys::SDL_bool::SDL_TRUE } else { sdl2_sys::SDL_bool::SDL_FALSE };
+		if unsafe { sdl2_sys::SDL_RenderSetIntegerScale(self.canvas.raw(), sdl_bool) } != 0 {
+			eprintln!("SDL2 SDL_RenderSetIntegerScale() failed. {}", sdl2::get_error());
+		}
+	}
+
 	/// returns the current framerate in frames per second
 	///
 	/// In the p5.js API there is one function as getter and setter, framerate(), which has an optional argument.
@@ -191,9 +861,66 @@ impl Sketch {
 	/// max. setting 200 fps;
 	/// In the p5.js API there is one function as getter and setter, framerate(), which has an optional argument.
 	pub fn set_framerate(&mut self, fps: u32) {
+		self.focused_framerate = fps;
+		self.apply_framerate();
+	}
+
+	/// sets the framerate of the FPSManager depending on the focus of the window
+	fn apply_framerate(&mut self) {
+		let fps = if !self.focused && self.unfocused_framerate > 0 { self.unfocused_framerate } else { self.focused_framerate };
 		self.fps_manager.set_framerate(fps).unwrap_or_else( |e| { eprintln!("SDL2-gfx set_framerate() failed. {}", e); } );
 	}
 
+	/// sets a reduced max. framerate which is used while the window does not have the focus (0 disables this, default)
+	///
+	/// This saves power for sketches running in the background. The framerate set by set_framerate() is restored as soon as the window gains the focus again.
+	pub fn set_unfocused_framerate(&mut self, fps: u32) {
+		self.unfocused_framerate = fps;
+		self.apply_framerate();
+	}
+
+	/// sets a fixed rate for update() in calls per second (0 disables this, default)
+	///
+	/// By default update() and draw() are called once per frame, so the speed of a simulation depends on the framerate. With a fixed update rate, update() is called as often as needed to keep up with the rate (possibly several times or not at all in a frame), while draw() is still called once per frame.
+	/// Then the per-frame input data (e.g. key_went_down() or mouse_delta()) refers to the time since the previous call of update().
+	pub fn set_update_rate(&mut self, rate: u32) {
+		self.update_step = if rate > 0 { 1000.0 / rate as f64 } else { 0.0 };
+		self.update_accumulator = self.update_step; // update() is called in the next frame
+	}
+
+	/// sets the max. number of update() calls per frame with a fixed update rate (default: 5)
+	///
+	/// This prevents the sketch from falling further and further behind if update() takes longer than an update step.
+	pub fn set_max_update_steps(&mut self, steps: u32) {
+		self.max_update_steps = steps.max(1);
+	}
+
+	/// returns how far the time has advanced between the last and the next call of update() in [0.0, 1.0)
+	///
+	/// With a fixed update rate, draw() can interpolate between the previous and the current state of a simulation with this factor to get smooth movements, e.g. prev_x + (x - prev_x) * s.update_alpha(). Without a fixed update rate, this is always 0.0.
+	pub fn update_alpha(&self) -> f32 {
+		if self.update_step == 0.0 {
+			return 0.0;
+		}
+		(self.update_accumulator / self.update_step) as f32
+	}
+
+	/// checks if the window currently has the keyboard focus
+	///
+	/// In the p5.js API this is the state variable focused.
+	pub fn is_focused(&self) -> bool {
+		self.focused
+	}
+
+	/// updates the focus state and switches between the normal and the unfocused framerate
+	fn set_focused(&mut self, focused: bool) {
+		if focused == self.focused {
+			return;
+		}
+		self.focused = focused;
+		self.apply_framerate();
+	}
+
 	/// delays the sketch to provide a constant framerate
 	fn delay(&mut self) {
 		self.fps_manager.delay();
@@ -206,6 +933,13 @@ impl Sketch {
 		self.no_loop = setting;
 	}
 
+	/// runs the main loop exactly once (update(), draw() and present), if it was stopped with no_loop(true)
+	///
+	/// Calling redraw() several times before the next frame results in a single frame. If the main loop is running, this has no effect.
+	pub fn redraw(&mut self) {
+		self.redraw = true;
+	}
+
 	/// exits the main loop
 	pub fn quit(&mut self) {
 		self.running = false;
@@ -216,6 +950,23 @@ impl Sketch {
 		self.canvas.present();
 	}
 
+	/// counts the frame and measures the time since the previous frame
+	fn begin_frame(&mut self) {
+		let fps = self.fps_manager.get_framerate() as u32;
+		self.clock.advance_frame(fps);
+		let now = self.clock.now_exact();
+		self.frame_count += 1;
+		self.frame_duration = (now - self.last_frame_time).max(0.0);
+		self.delta_time = self.frame_duration.round() as u32;
+		self.last_frame_time = now;
+	}
+
+	/// resets and stores per-frame input data at the end of a frame (or of an update step with a fixed update rate)
+	fn end_frame(&mut self) {
+		let mouse_pos = self.mouse_pos();
+		self.input.end_frame(mouse_pos);
+	}
+
 	/// clears the sketch by filling the whole sketch with the provided color
 	pub fn background(&mut self, color: Color) {
 		self.canvas.set_draw_color(color);
@@ -229,21 +980,561 @@ impl Sketch {
 
 	/// checks if the key with the provided keycode is currently pressed
 	pub fn key_is_down(&self, code: Keycode) -> bool {
-		self.keys_down.contains(&code)
+		self.input.keys_down.contains(&code)
+	}
+
+	/// checks if the key with the provided keycode was pressed since the last frame
+	///
+	/// Unlike key_is_down() this is only true in the first frame after pressing the key (key repeats are ignored). If the key was pressed and released within one frame, both key_went_down() and key_went_up() are true.
+	pub fn key_went_down(&self, code: Keycode) -> bool {
+		self.input.keys_went_down.contains(&code)
+	}
+
+	/// checks if the key with the provided keycode was released since the last frame
+	pub fn key_went_up(&self, code: Keycode) -> bool {
+		self.input.keys_went_up.contains(&code)
+	}
+
+	/// returns for how long the key with the provided keycode has been held down in milliseconds (0 if it is not pressed)
+	pub fn key_held_duration(&self, code: Keycode) -> u32 {
+		self.input.key_held_duration(code, self.clock.now())
+	}
+
+	/// checks if the key with the provided scancode is currently pressed
+	///
+	/// Scancodes refer to the physical position of a key independent of the keyboard layout, e.g. Scancode::W is always the key above Scancode::S.
+	pub fn scancode_is_down(&self, code: Scancode) -> bool {
+		self.input.scancodes_down.contains(&code)
+	}
+
+	/// starts or stops the text input, i.e. the TextInput events provided to key_typed()
+	///
+	/// Text input is enabled by default on desktop systems. On devices with an on-screen keyboard, starting the text input shows the keyboard.
+	pub fn text_input(&mut self, setting: bool) {
+		if setting {
+			unsafe { sdl2_sys::SDL_StartTextInput(); }
+		} else {
+			unsafe { sdl2_sys::SDL_StopTextInput(); }
+		}
 	}
 
 	/// returns current x position of the mouse in pixel coordinates
 	///
-	/// In the p5.js API there are two state variables for this (mouseX and mouseY). If the mouse pointer is outside the sketch window, the function returns last position of the mouse inside the window(!)
+	/// In the p5.js API there are two state variables for this (mouseX and mouseY). If the mouse pointer is outside the sketch window, the function returns last position of the mouse inside the window(!), which can be checked with mouse_in_window().
+	/// If a logical size is set, the position is returned in logical coordinates.
+	/// The position is taken from the mouse events handled by the sketch (it is (0, 0) before the first one), so pushed and replayed events behave exactly like real input.
 	pub fn mouse_pos(&self) -> (i32, i32) {
-		let mstate = MouseState::new(&self.event_pump);
-		(mstate.x(), mstate.y())
+		self.input.mouse_pos
+	}
+
+	/// returns the position of the mouse in the previous frame
+	///
+	/// In the p5.js API there are two state variables for this (pmouseX and pmouseY).
+	pub fn pmouse_pos(&self) -> (i32, i32) {
+		self.input.pmouse
+	}
+
+	/// checks if the mouse pointer is currently inside the sketch window
+	///
+	/// If it is not, mouse_pos() returns the last position of the mouse inside the window.
+	pub fn mouse_in_window(&self) -> bool {
+		unsafe { sdl2_sys::SDL_GetMouseFocus() == self.canvas.window().raw() }
+	}
+
+	/// converts window coordinates to canvas coordinates (differ if a logical size is set)
+	///
+	/// This is the same conversion SDL applies to the coordinates of mouse events.
+	fn window_to_canvas(&self, x: i32, y: i32) -> (i32, i32) {
+		let viewport = self.canvas.viewport();
+		let (scale_x, scale_y) = self.canvas.scale();
+		if scale_x <= 0.0 || scale_y <= 0.0 {
+			return (x, y);
+		}
+		((x as f32 / scale_x) as i32 - viewport.x(), (y as f32 / scale_y) as i32 - viewport.y())
+	}
+
+	/// returns the accumulated scroll distance of the mouse wheel (dx, dy) since the last frame
+	///
+	/// The value is reset after each frame, so it can be polled in update() or draw() instead of implementing mouse_wheel().
+	pub fn mouse_wheel_delta(&self) -> (f32, f32) {
+		self.input.mouse_wheel_delta
 	}
 
 	/// checks if any mouse button is currently pressed
+	///
+	/// Like mouse_pos(), this is taken from the mouse events handled by the sketch, including pushed and replayed events.
 	pub fn mouse_is_pressed(&self) -> bool {
-		let mstate = MouseState::new(&self.event_pump);
-		mstate.pressed_mouse_buttons().count() > 0
+		!self.input.mouse_buttons_down.is_empty()
+	}
+
+	/// checks if the provided mouse button is currently pressed
+	pub fn mouse_button_is_down(&self, button: MouseButton) -> bool {
+		self.input.mouse_buttons_down.contains(&button)
+	}
+
+	/// checks if the provided mouse button was pressed since the last frame
+	pub fn mouse_button_went_down(&self, button: MouseButton) -> bool {
+		self.input.mouse_buttons_went_down.contains(&button)
+	}
+
+	/// checks if the provided mouse button was released since the last frame
+	pub fn mouse_button_went_up(&self, button: MouseButton) -> bool {
+		self.input.mouse_buttons_went_up.contains(&button)
+	}
+
+	/// returns the distance (dx, dy) the mouse was moved since the last frame
+	pub fn mouse_delta(&self) -> (i32, i32) {
+		self.input.mouse_delta
+	}
+
+	/* time */
+
+	/// selects the clock of the sketch, which provides millis(), delta_time() and the framerate returned by get_framerate()
+	///
+	/// With the virtual clock the time advances by exactly 1/fps per frame (fps as set by set_framerate()), independent of how long a frame actually takes. This makes the time and everything depending on it, e.g. animations, reproducible, which is useful for tests.
+	pub fn clock_mode(&mut self, mode: ClockMode) {
+		self.clock.set_frame_based(mode == ClockMode::VIRTUAL);
+	}
+
+	/// returns the number of frames since the sketch was started
+	///
+	/// The counter is 0 in setup() and 1 in the first call of update() and draw(). In the p5.js API this is the state variable frameCount.
+	pub fn frame_count(&self) -> u64 {
+		self.frame_count
+	}
+
+	/// returns the time in milliseconds since the sketch was started
+	pub fn millis(&self) -> u32 {
+		self.clock.now()
+	}
+
+	/// returns the time in milliseconds between the start of the previous frame and the start of the current frame
+	///
+	/// Multiplying velocities with delta_time() makes movements independent of the framerate. In the p5.js API this is the state variable deltaTime.
+	pub fn delta_time(&self) -> u32 {
+		self.delta_time
+	}
+
+	/// returns the seconds of the current local time (0-59)
+	pub fn second(&self) -> i32 {
+		localtime::now().second
+	}
+
+	/// returns the minutes of the current local time (0-59)
+	pub fn minute(&self) -> i32 {
+		localtime::now().minute
+	}
+
+	/// returns the hour of the current local time (0-23)
+	pub fn hour(&self) -> i32 {
+		localtime::now().hour
+	}
+
+	/// returns the day of the month of the current local date (1-31)
+	pub fn day(&self) -> i32 {
+		localtime::now().day
+	}
+
+	/// returns the month of the current local date (1-12)
+	pub fn month(&self) -> i32 {
+		localtime::now().month
+	}
+
+	/// returns the year of the current local date, e.g. 2018
+	pub fn year(&self) -> i32 {
+		localtime::now().year
+	}
+
+	/* touch input */
+
+	/// returns all points where the screen is currently touched
+	///
+	/// In the p5.js API this is the state variable touches.
+	pub fn touches(&self) -> Vec<Touch> {
+		self.input.touches.values().cloned().collect()
+	}
+
+	/// enables or disables the mouse events SDL generates from touch input (enabled by default)
+	///
+	/// If disabled, touching the screen only calls the touch callbacks and not mouse_pressed() etc.
+	pub fn touch_mouse_events(&mut self, setting: bool) {
+		self.touch_mouse_events = setting;
+	}
+
+	/// converts the normalized touch coordinates of SDL to canvas coordinates
+	fn touch_to_canvas(&self, x: f32, y: f32) -> (i32, i32) {
+		let (w, h) = self.canvas.window().size();
+		self.window_to_canvas((x * w as f32) as i32, (y * h as f32) as i32)
+	}
+
+	/// creates a touch point from the values of a touch event
+	fn touch_point(&self, touch_id: i64, id: i64, x: f32, y: f32, dx: f32, dy: f32, pressure: f32) -> Touch {
+		let (tx, ty) = self.touch_to_canvas(x, y);
+		let (w, h) = self.canvas.window().size();
+		let (scale_x, scale_y) = self.canvas.scale();
+		Touch {
+			touch_id,
+			id,
+			x: tx,
+			y: ty,
+			dx: dx * w as f32 / scale_x.max(0.0001),
+			dy: dy * h as f32 / scale_y.max(0.0001),
+			pressure,
+		}
+	}
+
+	/* game controllers */
+
+	/// returns the ids of all connected game controllers
+	pub fn controllers(&self) -> Vec<i32> {
+		self.input.controllers.keys().cloned().collect()
+	}
+
+	/// returns the name of the game controller with the provided id (empty if unknown)
+	pub fn controller_name(&self, id: i32) -> String {
+		self.controller_devices.get(&id).map_or(String::new(), |d| d.0.name())
+	}
+
+	/// checks if the button of the game controller with the provided id is currently pressed
+	pub fn controller_button_is_down(&self, id: i32, button: ControllerButton) -> bool {
+		self.input.controller_button_is_down(id, button)
+	}
+
+	/// returns the current value of an axis of the game controller with the provided id
+	///
+	/// The value is between -1.0 and 1.0 (triggers: 0.0 to 1.0) with the dead zone applied.
+	pub fn controller_axis(&self, id: i32, axis: ControllerAxis) -> f32 {
+		self.input.controller_axis(id, axis)
+	}
+
+	/// sets the dead zone of all controller axes as a fraction of the full range (default: 0.1)
+	///
+	/// Axis values inside the dead zone are reported as 0.0, the remaining range is rescaled to reach 1.0 at full deflection.
+	pub fn controller_dead_zone(&mut self, dead_zone: f32) {
+		self.input.controller_dead_zone = utils::constrain(dead_zone, 0.0, 0.99);
+	}
+
+	/// lets the game controller with the provided id rumble with a strength between 0.0 and 1.0 for the provided duration in ms
+	///
+	/// Does nothing if the controller does not support rumble.
+	pub fn controller_rumble(&mut self, id: i32, strength: f32, duration: u32) {
+		if let Some(&mut (_, Some(ref mut haptic))) = self.controller_devices.get_mut(&id) {
+			haptic.rumble_play(utils::constrain(strength, 0.0, 1.0), duration);
+		}
+	}
+
+	/// opens the game controller with the provided joystick index and returns its id
+	///
+	/// If the controller cannot be opened (e.g. for synthetic events in tests), the joystick index is used as id.
+	fn open_controller(&mut self, index: u32) -> i32 {
+		let controller = match self.controller_subsystem {
+			Some(ref subsystem) => subsystem.open(index).map_err( |e| { eprintln!("Opening game controller {} failed. {}", index, e); } ).ok(),
+			None => None,
+		};
+		match controller {
+			Some(c) => {
+				let id = c.instance_id();
+				let haptic = self.haptic_subsystem.as_ref().and_then( |h| h.open_from_joystick_id(index).ok() );
+				self.controller_devices.insert(id, (c, haptic));
+				id
+			},
+			None => index as i32,
+		}
+	}
+
+	/* synthetic events */
+
+	/// adds an event to the end of the event queue of SDL
+	///
+	/// The event is handled after the events already in the queue exactly like an event from SDL, i.e. the callbacks of MainLoopMethods are called and the input state (including mouse_pos() and mouse_is_pressed()) is updated. This can be used to test a sketch without user input.
+	/// During a replay (see replay_input()) only pushed Quit events are handled.
+	pub fn push_event(&mut self, event: Event) {
+		events::push_event(&self.event_subsystem, event).unwrap_or_else( |e| { eprintln!("SDL2 push_event() failed. {}", e); } );
+	}
+
+	/// adds a KeyDown (down = true) or KeyUp event for the provided key to the event queue of the sketch (see push_event())
+	///
+	/// The scancode and the pressed modifier keys of the event are set like SDL does, e.g. after push_key(Keycode::LCtrl, true) the following key events have ctrl() set.
+	pub fn push_key(&mut self, keycode: Keycode, down: bool) {
+		self.synthetic_input.key(keycode, down);
+		let timestamp = unsafe { SDL_GetTicks() };
+		let window_id = self.canvas.window().id();
+		let scancode = Scancode::from_keycode(keycode);
+		let keymod = self.synthetic_input.keymod;
+		let event = if down {
+			Event::KeyDown { timestamp, window_id, keycode: Some(keycode), scancode, keymod, repeat: false }
+		} else {
+			Event::KeyUp { timestamp, window_id, keycode: Some(keycode), scancode, keymod, repeat: false }
+		};
+		self.push_event(event);
+	}
+
+	/// adds a MouseMotion event to the provided position (in canvas coordinates) to the event queue of the sketch (see push_event())
+	///
+	/// The relative motion is calculated from the previous position and the mouse buttons pressed with push_mouse_button() are set, so a drag can be simulated, too.
+	pub fn push_mouse_move(&mut self, x: i32, y: i32) {
+		let (px, py) = self.synthetic_input.pending_mouse_pos.unwrap_or_else( || self.mouse_pos() );
+		self.synthetic_input.pending_mouse_pos = Some((x, y));
+		let event = Event::MouseMotion {
+			timestamp: unsafe { SDL_GetTicks() },
+			window_id: self.canvas.window().id(),
+			which: 0,
+			mousestate: MouseState::from_sdl_state(self.synthetic_input.mouse_buttons),
+			x, y,
+			xrel: x - px,
+			yrel: y - py,
+		};
+		self.push_event(event);
+	}
+
+	/// adds a MouseButtonDown (down = true) or MouseButtonUp event at the current mouse position to the event queue of the sketch (see push_event())
+	///
+	/// clicks is the number of clicks in a row like in the events of SDL, i.e. a MouseButtonUp event with clicks = 2 completes a double click (see MainLoopMethods::double_clicked()).
+	pub fn push_mouse_button(&mut self, button: MouseButton, down: bool, clicks: u8) {
+		let (x, y) = self.synthetic_input.pending_mouse_pos.unwrap_or_else( || self.mouse_pos() );
+		self.synthetic_input.mouse_button(button, down);
+		let timestamp = unsafe { SDL_GetTicks() };
+		let window_id = self.canvas.window().id();
+		let event = if down {
+			Event::MouseButtonDown { timestamp, window_id, which: 0, mouse_btn: button, clicks, x, y }
+		} else {
+			Event::MouseButtonUp { timestamp, window_id, which: 0, mouse_btn: button, clicks, x, y }
+		};
+		self.push_event(event);
+	}
+
+	/// adds a TextInput event to the event queue of the sketch (see push_event()), i.e. key_typed() is called with the text
+	pub fn push_text(&mut self, text: &str) {
+		let event = Event::TextInput {
+			timestamp: unsafe { SDL_GetTicks() },
+			window_id: self.canvas.window().id(),
+			text: text.to_string(),
+		};
+		self.push_event(event);
+	}
+
+	/// returns the next replayed event or, if there is none, the next event from SDL
+	///
+	/// During a replay the input from SDL is skipped, only Quit events are passed on.
+	fn next_event(&mut self) -> Option<Event> {
+		if let Some(event) = self.replay_events.pop_front() {
+			return Some(event);
+		}
+		loop {
+			let event = self.waited_event.take().or_else( || self.event_pump.poll_event() )?;
+			match event {
+				Event::Quit { .. } => { return Some(event); },
+				_ if self.replay.is_none() => { return Some(event); },
+				_ => {}
+			}
+		}
+	}
+
+	/// blocks until an event is available or the timeout (in ms) has passed without using the CPU
+	///
+	/// The event is kept for next_event(), so it is handled like all other events.
+	fn wait_event(&mut self, timeout: u32) {
+		self.inject_replay_events();
+		if self.waited_event.is_some() || !self.replay_events.is_empty() {
+			return;
+		}
+		self.waited_event = self.event_pump.wait_event_timeout(timeout);
+	}
+
+	/* recording and replay */
+
+	/// records all events handled by the sketch to a file, so they can be replayed later with replay_input()
+	///
+	/// Each event is written as a JSON object in a separate line (JSON Lines) together with the frame number and the time since the start of the sketch. The first line contains the seed of the random number generator (see random_seed()).
+	/// If the environment variable SDL2SKETCH_RECORD is set to a file name, the recording starts automatically.
+	pub fn record_input(&mut self, path: &Path) -> Result<(), String> {
+		self.recorder = Some(Recorder::create(path, self.seed)?);
+		Ok(())
+	}
+
+	/// stops recording the events and closes the recording file
+	pub fn stop_recording(&mut self) {
+		if let Some(mut recorder) = self.recorder.take() {
+			recorder.flush();
+		}
+	}
+
+	/// feeds the events of a file written by record_input() back to the sketch at the recorded frames
+	///
+	/// This needs to be called before the sketch is started. For a deterministic replay the random number generator and the noise are seeded like in the recording and the virtual clock is used (see clock_mode()). While replaying, all input of the user is ignored, except for closing the window.
+	/// If the environment variable SDL2SKETCH_REPLAY is set to a file name, the replay starts automatically.
+	pub fn replay_input(&mut self, path: &Path) -> Result<(), String> {
+		let replay = Replay::load(path)?;
+		self.seed = replay.seed();
+		self.rng = rng_from_seed(self.seed);
+		self.noise = Noise::new(self.seed as u32);
+		self.clock_mode(ClockMode::VIRTUAL);
+		self.replay = Some(replay);
+		Ok(())
+	}
+
+	/// checks if recorded events are still being replayed (see replay_input())
+	pub fn is_replaying(&self) -> bool {
+		self.replay.is_some()
+	}
+
+	/// adds the recorded events of the current frame to the event queue
+	fn inject_replay_events(&mut self) {
+		let finished = match self.replay {
+			Some(ref mut replay) => {
+				let events = replay.events_until(self.frame_count, self.idle);
+				self.replay_events.extend(events);
+				replay.is_finished()
+			},
+			None => false,
+		};
+		if finished {
+			self.replay = None; // the input of the user is handled again
+		}
+	}
+
+	/// writes the event to the recording, if a recording is running
+	fn record_event(&mut self, event: &Event) {
+		let (frame, time, idle) = (self.frame_count, self.clock.now(), self.idle);
+		if let Some(ref mut recorder) = self.recorder {
+			recorder.record(frame, time, idle, event);
+		}
+	}
+
+	/* noise */
+
+	/// returns 1D Perlin noise in [0.0, 1.0) like noise(x) of p5.js (see utils::Noise)
+	///
+	/// Unless noise_seed() is called, the noise is seeded like the random number generator (see random_seed()), so it is the same when a recording is replayed.
+	pub fn noise(&self, x: f32) -> f32 {
+		self.noise.noise(x)
+	}
+
+	/// returns 2D Perlin noise in [0.0, 1.0) like noise(x, y) of p5.js
+	pub fn noise2(&self, x: f32, y: f32) -> f32 {
+		self.noise.noise2(x, y)
+	}
+
+	/// returns 3D Perlin noise in [0.0, 1.0) like noise(x, y, z) of p5.js
+	pub fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+		self.noise.noise3(x, y, z)
+	}
+
+	/// sets the number of octaves (default: 4) and the falloff factor (default: 0.5) of the noise
+	pub fn noise_detail(&mut self, octaves: u32, falloff: f32) {
+		self.noise.noise_detail(octaves, falloff);
+	}
+
+	/// sets the seed of the noise, the same seed always results in the same noise
+	pub fn noise_seed(&mut self, seed: u32) {
+		self.noise.noise_seed(seed);
+	}
+
+
+	/* random numbers */
+
+	/// sets the seed of the random number generator, the same seed always results in the same random numbers
+	///
+	/// All random functions of the sketch (e.g. random(), random_range() or shuffle()) use this generator. Unlike rand::thread_rng() it is seeded with the seed stored in a recording when it is replayed, so random values are the same as in the recording.
+	pub fn random_seed(&mut self, seed: u64) {
+		self.seed = seed;
+		self.rng = rng_from_seed(seed);
+	}
+
+	/// returns a random number in [0.0, max)
+	pub fn random(&mut self, max: f32) -> f32 {
+		self.rng.gen::<f32>() * max
+	}
+
+	/// returns a random number in [low, high), e.g. random_range(0, 6) for an integer between 0 and 5
+	///
+	/// Panics if low >= high.
+	pub fn random_range<T: PartialOrd + SampleRange>(&mut self, low: T, high: T) -> T {
+		self.rng.gen_range(low, high)
+	}
+
+	/// returns a random number from a normal distribution with the given mean and standard deviation
+	pub fn random_gaussian(&mut self, mean: f32, sd: f32) -> f32 {
+		let StandardNormal(n) = self.rng.gen::<StandardNormal>();
+		mean + sd * n as f32
+	}
+
+	/// returns a random element of the slice or None if it is empty
+	pub fn random_choice<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+		self.rng.choose(items)
+	}
+
+	/// returns a random element of the slice, the probability of each element is proportional to its weight (see utils::weighted_index())
+	///
+	/// Panics if items and weights have different lengths.
+	pub fn random_weighted_choice<'a, T>(&mut self, items: &'a [T], weights: &[f32]) -> Option<&'a T> {
+		assert_eq!(items.len(), weights.len(), "random_weighted_choice() needs one weight per item");
+		utils::weighted_index(&mut self.rng, weights).map( |i| &items[i] )
+	}
+
+	/// shuffles the elements of the slice randomly
+	pub fn shuffle<T>(&mut self, items: &mut [T]) {
+		self.rng.shuffle(items);
+	}
+
+
+	/* action bindings */
+
+	/// binds an input to the named action, an action can have several bindings
+	///
+	/// By default the action "quit", which quits the main loop, is bound to Escape. Use unbind_action("quit") to remove this behaviour.
+	pub fn bind_action(&mut self, name: &str, binding: Binding) {
+		self.actions.bind_action(name, binding);
+	}
+
+	/// removes all bindings of the named action
+	pub fn unbind_action(&mut self, name: &str) {
+		self.actions.unbind_action(name);
+	}
+
+	/// binds an input to the named axis, an axis can have several bindings
+	///
+	/// Digital inputs are bound as AxisBinding::Negative or AxisBinding::Positive, analog controller axes as AxisBinding::ControllerAxis.
+	pub fn bind_axis(&mut self, name: &str, binding: AxisBinding) {
+		self.actions.bind_axis(name, binding);
+	}
+
+	/// removes all bindings of the named axis
+	pub fn unbind_axis(&mut self, name: &str) {
+		self.actions.unbind_axis(name);
+	}
+
+	/// checks if any input bound to the named action is currently pressed
+	pub fn action_down(&self, name: &str) -> bool {
+		self.actions.action_down(name, &self.input)
+	}
+
+	/// checks if any input bound to the named action was pressed since the last frame
+	pub fn action_went_down(&self, name: &str) -> bool {
+		self.actions.action_went_down(name, &self.input)
+	}
+
+	/// checks if an input bound to the named action was released since the last frame and none of its inputs is still pressed
+	pub fn action_went_up(&self, name: &str) -> bool {
+		self.actions.action_went_up(name, &self.input)
+	}
+
+	/// returns the value of the named axis between -1.0 and 1.0 (sum of all bound inputs)
+	pub fn axis(&self, name: &str) -> f32 {
+		self.actions.axis(name, &self.input)
+	}
+
+	/// replaces all action and axis bindings with the bindings from a file
+	///
+	/// The file contains one binding per line, e.g. "action jump = key:Space" or "axis move_x = negative key:Left", as written by save_bindings().
+	pub fn load_bindings(&mut self, filename: &Path) -> Result<(), String> {
+		let text = fs::read_to_string(filename).map_err( |e| format!("Error reading bindings from {}. {}", filename.display(), e) )?;
+		self.actions.parse(&text).map_err( |e| format!("Error parsing bindings from {}. {}", filename.display(), e) )
+	}
+
+	/// saves all action and axis bindings to a file (see load_bindings())
+	pub fn save_bindings(&self, filename: &Path) -> Result<(), String> {
+		fs::write(filename, self.actions.to_string()).map_err( |e| format!("Error writing bindings to {}. {}", filename.display(), e) )
 	}
 
 	/* draw settings */
@@ -483,9 +1774,16 @@ impl Sketch {
 	/* draw images */
 
 	/// loads an image from file (PNG or JPG)
+	///
+	/// Aborts if the image cannot be loaded, see try_load_image() for files which might not be valid images (e.g. dropped by the user).
 	pub fn load_image(&mut self, filename: &Path) -> Image {
-		let surf = Surface::from_file(filename).expect("Error loading image. Abort.");
-		self.texture_creator.create_texture_from_surface(surf).expect("Error converting image to texture. Abort.")
+		self.try_load_image(filename).unwrap_or_else( |e| { panic!("{} Abort.", e); } )
+	}
+
+	/// loads an image from file (PNG or JPG) and returns an error message if this fails
+	pub fn try_load_image(&mut self, filename: &Path) -> Result<Image, String> {
+		let surf = Surface::from_file(filename).map_err( |e| format!("Error loading image {}. {}", filename.display(), e) )?;
+		self.texture_creator.create_texture_from_surface(surf).map_err( |e| format!("Error converting image {} to texture. {}", filename.display(), e) )
 	}
 
 	/// displays an image at position (x,y) in size (w,h)
@@ -551,6 +1849,15 @@ pub enum AngleMode {
 	DEGREES,
 }
 
+/// options for the clock of a sketch
+#[derive(PartialEq)]
+pub enum ClockMode {
+	/// REALTIME (default): the time of the system
+	REALTIME,
+	/// VIRTUAL: the time advances by exactly the duration of one frame (1/fps) per frame
+	VIRTUAL,
+}
+
 /// options for the interpretation of the parameters given to rect()
 #[derive(PartialEq)]
 pub enum RectMode {
@@ -576,20 +1883,125 @@ pub enum ImageMode {
 }
 
 
+/// information about a key event as provided to MainLoopMethods::key_pressed() and key_released()
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyEvent {
+	/// keycode of the key (depends on the keyboard layout)
+	pub keycode: Keycode,
+	/// scancode of the key (physical position of the key, independent of the keyboard layout)
+	pub scancode: Option<Scancode>,
+	/// state of the modifier keys (Shift, Ctrl, Alt, ...) when the event occurred
+	pub keymod: KeyMod,
+	/// true if the event was generated by holding the key down (key repeat)
+	pub repeat: bool,
+}
+
+impl KeyEvent {
+	/// checks if one of the Shift keys was pressed
+	pub fn shift(&self) -> bool {
+		self.keymod.intersects(sdl2::keyboard::LSHIFTMOD | sdl2::keyboard::RSHIFTMOD)
+	}
+
+	/// checks if one of the Ctrl keys was pressed
+	pub fn ctrl(&self) -> bool {
+		self.keymod.intersects(sdl2::keyboard::LCTRLMOD | sdl2::keyboard::RCTRLMOD)
+	}
+
+	/// checks if one of the Alt keys was pressed
+	pub fn alt(&self) -> bool {
+		self.keymod.intersects(sdl2::keyboard::LALTMOD | sdl2::keyboard::RALTMOD)
+	}
+
+	/// checks if one of the GUI keys (Windows/Command key) was pressed
+	pub fn gui(&self) -> bool {
+		self.keymod.intersects(sdl2::keyboard::LGUIMOD | sdl2::keyboard::RGUIMOD)
+	}
+}
+
+/// a point where the screen is touched as provided to the touch callbacks of MainLoopMethods and by Sketch::touches()
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Touch {
+	/// id of the touch device
+	pub touch_id: i64,
+	/// id of the finger, which stays the same while the finger touches the screen
+	///
+	/// The id is only unique for a touch device, i.e. fingers on different devices can have the same id.
+	pub id: i64,
+	/// x position in pixel coordinates of the sketch (like mouse_pos())
+	pub x: i32,
+	/// y position in pixel coordinates of the sketch (like mouse_pos())
+	pub y: i32,
+	/// distance in x direction the finger moved with the last event
+	pub dx: f32,
+	/// distance in y direction the finger moved with the last event
+	pub dy: f32,
+	/// pressure of the finger between 0.0 and 1.0 (if supported by the device)
+	pub pressure: f32,
+}
+
+/// information about a mouse drag as provided to MainLoopMethods::mouse_dragged()
+#[derive(Clone, Debug, PartialEq)]
+pub struct MouseDrag {
+	/// all mouse buttons that are currently pressed
+	pub buttons: Vec<MouseButton>,
+	/// x position where the drag started, i.e. where the first of the buttons was pressed
+	pub start_x: i32,
+	/// y position where the drag started, i.e. where the first of the buttons was pressed
+	pub start_y: i32,
+}
+
+impl MouseDrag {
+	/// checks if the provided mouse button is pressed during the drag
+	pub fn is_pressed(&self, button: MouseButton) -> bool {
+		self.buttons.contains(&button)
+	}
+}
+
+/// information about a display as returned by Sketch::displays()
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisplayInfo {
+	/// index of the display (used by Sketch::fullscreen_on_display())
+	pub index: i32,
+	/// name of the display (may be empty)
+	pub name: String,
+	/// x position of the display in the desktop area
+	pub x: i32,
+	/// y position of the display in the desktop area
+	pub y: i32,
+	/// width of the display in pixels
+	pub width: i32,
+	/// height of the display in pixels
+	pub height: i32,
+	/// refresh rate of the display in Hz (0 if unknown)
+	pub refresh_rate: i32,
+}
+
+
 /// initializes the necessary SDL2 subsystems and returns a SDL2 window/renderer and event pump
-fn init_sdl_subsystems(width: u32, height: u32, title: &str) -> (Canvas<sdl2::video::Window>, EventPump, Sdl2ImageContext) {
+///
+/// With SDL's dummy video driver (SDL_VIDEODRIVER=dummy) a software renderer is used, so sketches can run headless.
+fn init_sdl_subsystems(width: u32, height: u32, title: &str) -> (sdl2::Sdl, Canvas<sdl2::video::Window>, EventPump, Sdl2ImageContext) {
 	let sdl_context = sdl2::init().expect("SDL2 init() failed. Abort.");
 	let video_subsystem = sdl_context.video().expect("Initialization of SDL2 video subsystem failed. Abort.");
-	let window = video_subsystem.window(title, width, height)
-		.position_centered()
-		.opengl()
-		.build().expect("Initialization of SDL2 window failed. Abort.");
-	let canvas = window.into_canvas()
-		.accelerated()
-		.build().expect("Initialization of SDL2 canvas failed. Abort.");
+	let headless = video_subsystem.current_video_driver() == "dummy";
+	let mut window_builder = video_subsystem.window(title, width, height);
+	window_builder.position_centered();
+	if !headless {
+		window_builder.opengl();
+	}
+	let window = window_builder.build().expect("Initialization of SDL2 window failed. Abort.");
+	let canvas_builder = if headless { window.into_canvas().software() } else { window.into_canvas().accelerated() };
+	let canvas = canvas_builder.build().expect("Initialization of SDL2 canvas failed. Abort.");
 	let event_pump = sdl_context.event_pump().expect("Initialization of SDL2 event pump failed. Abort.");
 	let image_context = sdl2::image::init(sdl2::image::INIT_PNG | sdl2::image::INIT_JPG).expect("Initialization of SDL2-image failed. Abort.");
-	(canvas, event_pump, image_context)
+	(sdl_context, canvas, event_pump, image_context)
+}
+
+
+/// creates a random number generator from a seed
+fn rng_from_seed(seed: u64) -> XorShiftRng {
+	let (low, high) = (seed as u32, (seed >> 32) as u32);
+	XorShiftRng::from_seed([low, high, low ^ 0x9e37_79b9, high ^ 0x7f4a_7c15]) // the seed must not be all zeros
 }
 
 
@@ -608,23 +2020,28 @@ impl FPSData {
 			update_interval,
 			print_fps: env::var("SDL2SKETCH_PRINTFPS").is_ok(),
 			current_fps: 0.0,
-			last_update: unsafe { SDL_GetTicks() },
+			last_update: 0,
 			num_frames: 0,
 		}
 	}
 
-	fn update(&mut self) {
-		let now = unsafe { SDL_GetTicks() };
-		let time_diff = now - self.last_update;
-		if time_diff > self.update_interval {
+	/// starts a new measurement at the time 0 of the clock of the sketch
+	fn restart(&mut self) {
+		self.last_update = 0;
+		self.num_frames = 0;
+	}
+
+	/// counts a frame, now is the current time of the clock of the sketch in ms
+	fn update(&mut self, now: u32) {
+		self.num_frames += 1;
+		let time_diff = now.wrapping_sub(self.last_update);
+		if time_diff >= self.update_interval {
 			self.current_fps = (self.num_frames as f32 / time_diff as f32) * 1000.0;
 			self.last_update = now;
 			self.num_frames = 0;
 			if self.print_fps {
 				println!("FPS: {:.2}", self.current_fps);
 			}
-		} else {
-			self.num_frames += 1;
 		}
 	}
 }
@@ -0,0 +1,68 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use libc;
+
+/// the current local date and time, see Sketch::second() to Sketch::year()
+pub struct LocalTime {
+	pub second: i32, // 0-59
+	pub minute: i32, // 0-59
+	pub hour: i32, // 0-23
+	pub day: i32, // 1-31
+	pub month: i32, // 1-12
+	pub year: i32,
+}
+
+/// returns the current local time as provided by the C library
+///
+/// If the C library cannot convert the time, the UTC time is returned.
+pub fn now() -> LocalTime {
+	let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0) as i64;
+	match local_tm(secs) {
+		Some(tm) => LocalTime {
+			second: tm.tm_sec.min(59), // tm_sec can be 60 for leap seconds
+			minute: tm.tm_min,
+			hour: tm.tm_hour,
+			day: tm.tm_mday,
+			month: tm.tm_mon + 1,
+			year: tm.tm_year + 1900,
+		},
+		None => utc(secs),
+	}
+}
+
+#[cfg(windows)]
+fn local_tm(secs: i64) -> Option<libc::tm> {
+	let time = secs as libc::time_t;
+	let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+	// localtime_s() of the MSVC runtime returns 0 on success
+	match unsafe { libc::localtime_s(&mut tm, &time) } {
+		0 => Some(tm),
+		_ => None,
+	}
+}
+
+#[cfg(not(windows))]
+fn local_tm(secs: i64) -> Option<libc::tm> {
+	let time = secs as libc::time_t;
+	let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+	// localtime_r() returns a null pointer on failure
+	match unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+		false => Some(tm),
+		true => None,
+	}
+}
+
+/// converts the seconds since the Unix epoch to the UTC date and time
+fn utc(secs: i64) -> LocalTime {
+	let (days, rest) = (secs.div_euclid(86400), secs.rem_euclid(86400) as i32);
+	// civil date from days, see http://howardhinnant.github.io/date_algorithms.html
+	let z = days + 719468;
+	let era = z.div_euclid(146097);
+	let doe = z - era * 146097;
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = (doy - (153 * mp + 2) / 5 + 1) as i32;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 } as i32;
+	let year = (yoe + era * 400) as i32 + if month <= 2 { 1 } else { 0 };
+	LocalTime { second: rest % 60, minute: rest / 60 % 60, hour: rest / 3600, day, month, year }
+}
@@ -0,0 +1,543 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Scancode, Mod};
+use sdl2::mouse::{MouseState, MouseWheelDirection};
+use sdl2::controller::{Axis, Button};
+
+use actions::{mouse_button_name, mouse_button_from_name};
+
+/// version of the file format, written to the first line of a recording
+const FORMAT_VERSION: u32 = 1;
+
+
+/// This struct writes the events handled by a sketch to a recording file.
+///
+/// The file is in the JSON Lines format, i.e. each line is a JSON object. The first line contains the format version and the seed of the random number generator, e.g. {"sdl2sketch_recording":1,"seed":42}. Each following line contains an event together with the frame number and the time since the start of the sketch in ms, e.g.
+/// {"frame":12,"time":396,"type":"key_down","keycode":"Space","scancode":"Space","keymod":0,"repeat":false}
+/// Events handled while the main loop is stopped (see Sketch::no_loop()) are marked with "idle":true. The format only changes together with FORMAT_VERSION, recordings of other versions are rejected by Replay::load().
+pub struct Recorder {
+	writer: BufWriter<File>,
+}
+
+impl Recorder {
+	pub fn create(path: &Path, seed: u64) -> Result<Self, String> {
+		let file = File::create(path).map_err( |e| format!("Cannot create recording {}. {}", path.display(), e) )?;
+		let mut recorder = Recorder { writer: BufWriter::new(file) };
+		recorder.write_line(&format!("{{\"sdl2sketch_recording\":{},\"seed\":{}}}", FORMAT_VERSION, seed));
+		Ok(recorder)
+	}
+
+	/// writes the event to the recording, events which are not handled by a sketch are skipped
+	pub fn record(&mut self, frame: u64, time: u32, idle: bool, event: &Event) {
+		let mut line = Line::new(frame, time, idle);
+		if write_event(&mut line, event) {
+			self.write_line(&line.finish());
+		}
+	}
+
+	pub fn flush(&mut self) {
+		self.writer.flush().unwrap_or_else( |e| { eprintln!("Writing the recording failed. {}", e); } );
+	}
+
+	fn write_line(&mut self, line: &str) {
+		writeln!(self.writer, "{}", line).unwrap_or_else( |e| { eprintln!("Writing the recording failed. {}", e); } );
+	}
+}
+
+
+/// This struct contains an event read from a recording file.
+struct RecordedEvent {
+	frame: u64,
+	idle: bool,
+	event: Event,
+}
+
+/// This struct contains the events of a recording file, which are fed back to a sketch at the recorded frames (see Recorder).
+pub struct Replay {
+	seed: u64,
+	events: VecDeque<RecordedEvent>,
+}
+
+impl Replay {
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let file = File::open(path).map_err( |e| format!("Cannot open recording {}. {}", path.display(), e) )?;
+		let mut lines = BufReader::new(file).lines();
+		let header = match lines.next() {
+			Some(line) => line.map_err( |e| e.to_string() )?,
+			None => { return Err(format!("Recording {} is empty.", path.display())); }
+		};
+		let header = Fields::parse(&header).map_err( |e| format!("{} is not a recording of SDL2Sketch. {}", path.display(), e) )?;
+		let version: u32 = header.get("sdl2sketch_recording").map_err( |e| format!("{} is not a recording of SDL2Sketch. {}", path.display(), e) )?;
+		if version != FORMAT_VERSION {
+			return Err(format!("Recording {} has the unsupported format version {}.", path.display(), version));
+		}
+		let seed = header.get("seed").map_err( |e| format!("Error in recording {}: {}", path.display(), e) )?;
+
+		let mut replay = Replay { seed, events: VecDeque::new() };
+		for (i, line) in lines.enumerate() {
+			let line = line.map_err( |e| e.to_string() )?;
+			if line.trim().is_empty() {
+				continue;
+			}
+			let recorded = Fields::parse(&line)
+				.and_then( |fields| Ok(RecordedEvent { frame: fields.get("frame")?, idle: fields.get("idle").unwrap_or(false), event: read_event(&fields)? }) )
+				.map_err( |e| format!("Error in line {} of recording {}: {}", i + 2, path.display(), e) )?;
+			replay.events.push_back(recorded);
+		}
+		Ok(replay)
+	}
+
+	pub fn seed(&self) -> u64 {
+		self.seed
+	}
+
+	/// removes and returns the events recorded up to the provided frame
+	///
+	/// Events which were handled while the main loop was stopped are only returned if idle is set or the frame is over.
+	pub fn events_until(&mut self, frame: u64, idle: bool) -> Vec<Event> {
+		let mut events = Vec::new();
+		while let Some(next) = self.events.front() {
+			if next.frame > frame || (next.frame == frame && next.idle && !idle) {
+				break;
+			}
+			events.push(next.event.clone());
+			self.events.pop_front();
+		}
+		events
+	}
+
+	pub fn is_finished(&self) -> bool {
+		self.events.is_empty()
+	}
+}
+
+
+/// adds the type and the fields of the event to the line, returns false if the event is not recorded
+fn write_event(line: &mut Line, event: &Event) -> bool {
+	match *event {
+		Event::Quit { .. } => {
+			line.kind("quit");
+		},
+		Event::Window { win_event, .. } => {
+			line.kind("window");
+			let (name, data) = window_event_name(win_event);
+			line.string("window_event", name);
+			if let Some((data1, data2)) = data {
+				line.field("data1", data1);
+				line.field("data2", data2);
+			}
+		},
+		Event::KeyDown { keycode, scancode, keymod, repeat, .. } | Event::KeyUp { keycode, scancode, keymod, repeat, .. } => {
+			line.kind(if let Event::KeyDown { .. } = *event { "key_down" } else { "key_up" });
+			if let Some(code) = keycode {
+				line.string("keycode", &code.name());
+			}
+			if let Some(code) = scancode {
+				line.string("scancode", code.name());
+			}
+			line.field("keymod", keymod.bits());
+			line.field("repeat", repeat);
+		},
+		Event::TextEditing { ref text, start, length, .. } => {
+			line.kind("text_editing");
+			line.string("text", text);
+			line.field("start", start);
+			line.field("length", length);
+		},
+		Event::TextInput { ref text, .. } => {
+			line.kind("text_input");
+			line.string("text", text);
+		},
+		Event::MouseMotion { which, mousestate, x, y, xrel, yrel, .. } => {
+			line.kind("mouse_motion");
+			line.field("which", which);
+			line.field("mousestate", mousestate.to_sdl_state());
+			line.field("x", x);
+			line.field("y", y);
+			line.field("xrel", xrel);
+			line.field("yrel", yrel);
+		},
+		Event::MouseButtonDown { which, mouse_btn, clicks, x, y, .. } | Event::MouseButtonUp { which, mouse_btn, clicks, x, y, .. } => {
+			line.kind(if let Event::MouseButtonDown { .. } = *event { "mouse_button_down" } else { "mouse_button_up" });
+			line.field("which", which);
+			line.string("button", mouse_button_name(mouse_btn));
+			line.field("clicks", clicks);
+			line.field("x", x);
+			line.field("y", y);
+		},
+		Event::MouseWheel { which, x, y, direction, .. } => {
+			line.kind("mouse_wheel");
+			line.field("which", which);
+			line.field("x", x);
+			line.field("y", y);
+			line.field("flipped", direction == MouseWheelDirection::Flipped);
+		},
+		Event::DropFile { ref filename, .. } => {
+			line.kind("drop_file");
+			line.string("filename", filename);
+		},
+		Event::FingerDown { touch_id, finger_id, x, y, dx, dy, pressure, .. }
+		| Event::FingerMotion { touch_id, finger_id, x, y, dx, dy, pressure, .. }
+		| Event::FingerUp { touch_id, finger_id, x, y, dx, dy, pressure, .. } => {
+			line.kind(match *event {
+				Event::FingerDown { .. }   => "finger_down",
+				Event::FingerMotion { .. } => "finger_motion",
+				_                          => "finger_up",
+			});
+			line.field("touch_id", touch_id);
+			line.field("finger_id", finger_id);
+			line.field("x", x);
+			line.field("y", y);
+			line.field("dx", dx);
+			line.field("dy", dy);
+			line.field("pressure", pressure);
+		},
+		Event::MultiGesture { touch_id, d_theta, d_dist, x, y, num_fingers, .. } => {
+			line.kind("multi_gesture");
+			line.field("touch_id", touch_id);
+			line.field("d_theta", d_theta);
+			line.field("d_dist", d_dist);
+			line.field("x", x);
+			line.field("y", y);
+			line.field("num_fingers", num_fingers);
+		},
+		Event::ControllerDeviceAdded { which, .. } => {
+			line.kind("controller_device_added");
+			line.field("which", which);
+		},
+		Event::ControllerDeviceRemoved { which, .. } => {
+			line.kind("controller_device_removed");
+			line.field("which", which);
+		},
+		Event::ControllerButtonDown { which, button, .. } | Event::ControllerButtonUp { which, button, .. } => {
+			line.kind(if let Event::ControllerButtonDown { .. } = *event { "controller_button_down" } else { "controller_button_up" });
+			line.field("which", which);
+			line.string("button", &button.string());
+		},
+		Event::ControllerAxisMotion { which, axis, value, .. } => {
+			line.kind("controller_axis_motion");
+			line.field("which", which);
+			line.string("axis", &axis.string());
+			line.field("value", value);
+		},
+		_ => { return false; }
+	}
+	true
+}
+
+/// creates the recorded event from the fields of a line of the recording
+fn read_event(fields: &Fields) -> Result<Event, String> {
+	let timestamp = fields.get("time")?;
+	Ok(match fields.text("type")? {
+		"quit" => Event::Quit { timestamp },
+		"window" => Event::Window {
+			timestamp,
+			window_id: 0,
+			win_event: window_event_from_name(fields.text("window_event")?, fields.get("data1").unwrap_or(0), fields.get("data2").unwrap_or(0))?,
+		},
+		kind @ "key_down" | kind @ "key_up" => {
+			let keycode = match fields.optional_text("keycode") {
+				Some(name) => Some(Keycode::from_name(name).ok_or_else( || format!("unknown keycode \"{}\"", name) )?),
+				None => None,
+			};
+			let scancode = match fields.optional_text("scancode") {
+				Some(name) => Some(Scancode::from_name(name).ok_or_else( || format!("unknown scancode \"{}\"", name) )?),
+				None => None,
+			};
+			let keymod = Mod::from_bits_truncate(fields.get("keymod")?);
+			let repeat = fields.get("repeat")?;
+			if kind == "key_down" {
+				Event::KeyDown { timestamp, window_id: 0, keycode, scancode, keymod, repeat }
+			} else {
+				Event::KeyUp { timestamp, window_id: 0, keycode, scancode, keymod, repeat }
+			}
+		},
+		"text_editing" => Event::TextEditing {
+			timestamp,
+			window_id: 0,
+			text: fields.text("text")?.to_string(),
+			start: fields.get("start")?,
+			length: fields.get("length")?,
+		},
+		"text_input" => Event::TextInput { timestamp, window_id: 0, text: fields.text("text")?.to_string() },
+		"mouse_motion" => Event::MouseMotion {
+			timestamp,
+			window_id: 0,
+			which: fields.get("which")?,
+			mousestate: MouseState::from_sdl_state(fields.get("mousestate")?),
+			x: fields.get("x")?,
+			y: fields.get("y")?,
+			xrel: fields.get("xrel")?,
+			yrel: fields.get("yrel")?,
+		},
+		kind @ "mouse_button_down" | kind @ "mouse_button_up" => {
+			let name = fields.text("button")?;
+			let mouse_btn = mouse_button_from_name(name).ok_or_else( || format!("unknown mouse button \"{}\"", name) )?;
+			let (which, clicks, x, y) = (fields.get("which")?, fields.get("clicks")?, fields.get("x")?, fields.get("y")?);
+			if kind == "mouse_button_down" {
+				Event::MouseButtonDown { timestamp, window_id: 0, which, mouse_btn, clicks, x, y }
+			} else {
+				Event::MouseButtonUp { timestamp, window_id: 0, which, mouse_btn, clicks, x, y }
+			}
+		},
+		"mouse_wheel" => Event::MouseWheel {
+			timestamp,
+			window_id: 0,
+			which: fields.get("which")?,
+			x: fields.get("x")?,
+			y: fields.get("y")?,
+			direction: if fields.get("flipped")? { MouseWheelDirection::Flipped } else { MouseWheelDirection::Normal },
+		},
+		"drop_file" => Event::DropFile { timestamp, filename: fields.text("filename")?.to_string() },
+		kind @ "finger_down" | kind @ "finger_motion" | kind @ "finger_up" => {
+			let (touch_id, finger_id) = (fields.get("touch_id")?, fields.get("finger_id")?);
+			let (x, y, dx, dy) = (fields.get("x")?, fields.get("y")?, fields.get("dx")?, fields.get("dy")?);
+			let pressure = fields.get("pressure")?;
+			match kind {
+				"finger_down"   => Event::FingerDown { timestamp, touch_id, finger_id, x, y, dx, dy, pressure },
+				"finger_motion" => Event::FingerMotion { timestamp, touch_id, finger_id, x, y, dx, dy, pressure },
+				_               => Event::FingerUp { timestamp, touch_id, finger_id, x, y, dx, dy, pressure },
+			}
+		},
+		"multi_gesture" => Event::MultiGesture {
+			timestamp,
+			touch_id: fields.get("touch_id")?,
+			d_theta: fields.get("d_theta")?,
+			d_dist: fields.get("d_dist")?,
+			x: fields.get("x")?,
+			y: fields.get("y")?,
+			num_fingers: fields.get("num_fingers")?,
+		},
+		"controller_device_added" => Event::ControllerDeviceAdded { timestamp, which: fields.get("which")? },
+		"controller_device_removed" => Event::ControllerDeviceRemoved { timestamp, which: fields.get("which")? },
+		kind @ "controller_button_down" | kind @ "controller_button_up" => {
+			let which = fields.get("which")?;
+			let name = fields.text("button")?;
+			let button = Button::from_string(name).ok_or_else( || format!("unknown controller button \"{}\"", name) )?;
+			if kind == "controller_button_down" {
+				Event::ControllerButtonDown { timestamp, which, button }
+			} else {
+				Event::ControllerButtonUp { timestamp, which, button }
+			}
+		},
+		"controller_axis_motion" => {
+			let name = fields.text("axis")?;
+			Event::ControllerAxisMotion {
+				timestamp,
+				which: fields.get("which")?,
+				axis: Axis::from_string(name).ok_or_else( || format!("unknown controller axis \"{}\"", name) )?,
+				value: fields.get("value")?,
+			}
+		},
+		kind => { return Err(format!("unknown event type \"{}\"", kind)); }
+	})
+}
+
+fn window_event_name(win_event: WindowEvent) -> (&'static str, Option<(i32, i32)>) {
+	match win_event {
+		WindowEvent::None              => ("none", None),
+		WindowEvent::Shown             => ("shown", None),
+		WindowEvent::Hidden            => ("hidden", None),
+		WindowEvent::Exposed           => ("exposed", None),
+		WindowEvent::Moved(x, y)       => ("moved", Some((x, y))),
+		WindowEvent::Resized(w, h)     => ("resized", Some((w, h))),
+		WindowEvent::SizeChanged(w, h) => ("size_changed", Some((w, h))),
+		WindowEvent::Minimized         => ("minimized", None),
+		WindowEvent::Maximized         => ("maximized", None),
+		WindowEvent::Restored          => ("restored", None),
+		WindowEvent::Enter             => ("enter", None),
+		WindowEvent::Leave             => ("leave", None),
+		WindowEvent::FocusGained       => ("focus_gained", None),
+		WindowEvent::FocusLost         => ("focus_lost", None),
+		WindowEvent::Close             => ("close", None),
+		WindowEvent::TakeFocus         => ("take_focus", None),
+		WindowEvent::HitTest           => ("hit_test", None),
+	}
+}
+
+fn window_event_from_name(name: &str, data1: i32, data2: i32) -> Result<WindowEvent, String> {
+	Ok(match name {
+		"none"         => WindowEvent::None,
+		"shown"        => WindowEvent::Shown,
+		"hidden"       => WindowEvent::Hidden,
+		"exposed"      => WindowEvent::Exposed,
+		"moved"        => WindowEvent::Moved(data1, data2),
+		"resized"      => WindowEvent::Resized(data1, data2),
+		"size_changed" => WindowEvent::SizeChanged(data1, data2),
+		"minimized"    => WindowEvent::Minimized,
+		"maximized"    => WindowEvent::Maximized,
+		"restored"     => WindowEvent::Restored,
+		"enter"        => WindowEvent::Enter,
+		"leave"        => WindowEvent::Leave,
+		"focus_gained" => WindowEvent::FocusGained,
+		"focus_lost"   => WindowEvent::FocusLost,
+		"close"        => WindowEvent::Close,
+		"take_focus"   => WindowEvent::TakeFocus,
+		"hit_test"     => WindowEvent::HitTest,
+		_ => { return Err(format!("unknown window event \"{}\"", name)); }
+	})
+}
+
+
+/// This struct builds a line of a recording, i.e. a flat JSON object with the frame, the time, the event type and the fields of the event.
+struct Line {
+	text: String,
+}
+
+impl Line {
+	fn new(frame: u64, time: u32, idle: bool) -> Self {
+		let mut line = Line { text: String::from("{") };
+		line.field("frame", frame);
+		line.field("time", time);
+		if idle {
+			line.field("idle", true);
+		}
+		line
+	}
+
+	fn kind(&mut self, kind: &str) {
+		self.string("type", kind);
+	}
+
+	/// adds a number or bool
+	fn field<T: Display>(&mut self, key: &str, value: T) {
+		self.key(key);
+		self.text.push_str(&value.to_string()); // Display of floats is the shortest representation which reads back to the same value
+	}
+
+	fn string(&mut self, key: &str, value: &str) {
+		self.key(key);
+		push_json_string(&mut self.text, value);
+	}
+
+	fn key(&mut self, key: &str) {
+		if self.text.len() > 1 {
+			self.text.push(',');
+		}
+		push_json_string(&mut self.text, key);
+		self.text.push(':');
+	}
+
+	fn finish(mut self) -> String {
+		self.text.push('}');
+		self.text
+	}
+}
+
+fn push_json_string(text: &mut String, s: &str) {
+	text.push('"');
+	for c in s.chars() {
+		match c {
+			'"'  => text.push_str("\\\""),
+			'\\' => text.push_str("\\\\"),
+			c if c.is_control() => text.push_str(&format!("\\u{:04x}", c as u32)),
+			c => text.push(c),
+		}
+	}
+	text.push('"');
+}
+
+/// This struct contains the values of a flat JSON object as written by Line, numbers and bools are kept as text and parsed to the needed type.
+struct Fields {
+	values: BTreeMap<String, String>,
+}
+
+impl Fields {
+	fn parse(line: &str) -> Result<Self, String> {
+		let mut chars = line.chars().peekable();
+		let mut values = BTreeMap::new();
+		expect(&mut chars, '{')?;
+		skip_whitespace(&mut chars);
+		if chars.peek() == Some(&'}') {
+			chars.next();
+			return Ok(Fields { values });
+		}
+		loop {
+			skip_whitespace(&mut chars);
+			let key = parse_json_string(&mut chars)?;
+			expect(&mut chars, ':')?;
+			skip_whitespace(&mut chars);
+			let value = if chars.peek() == Some(&'"') {
+				parse_json_string(&mut chars)?
+			} else {
+				let mut token = String::new();
+				while let Some(&c) = chars.peek() {
+					if c == ',' || c == '}' || c.is_whitespace() {
+						break;
+					}
+					token.push(c);
+					chars.next();
+				}
+				token
+			};
+			values.insert(key, value);
+			skip_whitespace(&mut chars);
+			match chars.next() {
+				Some(',') => {},
+				Some('}') => { return Ok(Fields { values }); },
+				_ => { return Err("expected ',' or '}'".to_string()); }
+			}
+		}
+	}
+
+	fn get<T: FromStr>(&self, key: &str) -> Result<T, String> {
+		let value = self.text(key)?;
+		value.parse().map_err( |_| format!("invalid value \"{}\" for \"{}\"", value, key) )
+	}
+
+	fn text(&self, key: &str) -> Result<&str, String> {
+		self.optional_text(key).ok_or_else( || format!("missing \"{}\"", key) )
+	}
+
+	fn optional_text(&self, key: &str) -> Option<&str> {
+		self.values.get(key).map( |v| v.as_str() )
+	}
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+	while chars.peek().is_some_and( |c| c.is_whitespace() ) {
+		chars.next();
+	}
+}
+
+fn expect(chars: &mut Peekable<Chars>, expected: char) -> Result<(), String> {
+	skip_whitespace(chars);
+	match chars.next() {
+		Some(c) if c == expected => Ok(()),
+		_ => Err(format!("expected '{}'", expected)),
+	}
+}
+
+fn parse_json_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+	expect(chars, '"')?;
+	let mut s = String::new();
+	loop {
+		match chars.next() {
+			Some('"') => { return Ok(s); },
+			Some('\\') => {
+				match chars.next() {
+					Some('n') => s.push('\n'),
+					Some('r') => s.push('\r'),
+					Some('t') => s.push('\t'),
+					Some('b') => s.push('\u{8}'),
+					Some('f') => s.push('\u{c}'),
+					Some('u') => {
+						let hex: String = chars.by_ref().take(4).collect();
+						let c = u32::from_str_radix(&hex, 16).ok().and_then(::std::char::from_u32);
+						s.push(c.ok_or_else( || format!("invalid escape \\u{}", hex) )?);
+					},
+					Some(c) => s.push(c), // \" \\ and \/
+					None => { return Err("unexpected end of line".to_string()); }
+				}
+			},
+			Some(c) => s.push(c),
+			None => { return Err("unterminated string".to_string()); }
+		}
+	}
+}
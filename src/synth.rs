@@ -0,0 +1,436 @@
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use sdl2::audio::{AudioCallback, AudioSpecDesired};
+
+use Sketch;
+use analyzer::{Analyzer, AnalyzerInput};
+
+/// default sample rate of the synth in Hz
+pub const DEFAULT_SAMPLE_RATE: i32 = 44_100;
+
+
+/// options for the waveform of an oscillator
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Waveform {
+	/// SINE (default): sine wave
+	SINE,
+	/// SQUARE: square wave with a duty cycle of 50%
+	SQUARE,
+	/// SAW: rising sawtooth wave
+	SAW,
+	/// TRIANGLE: triangle wave
+	TRIANGLE,
+	/// NOISE: white noise (the frequency is ignored)
+	NOISE,
+}
+
+impl Waveform {
+	fn from_index(index: usize) -> Self {
+		match index {
+			1 => Waveform::SQUARE,
+			2 => Waveform::SAW,
+			3 => Waveform::TRIANGLE,
+			4 => Waveform::NOISE,
+			_ => Waveform::SINE,
+		}
+	}
+}
+
+
+/// This struct contains a parameter of a synth, which can be set from update() while the synth is playing.
+///
+/// The value is stored in an atomic, so setting or reading it never waits for a lock, neither in the sketch nor in the audio callback. Clones share the same value.
+#[derive(Clone, Debug)]
+pub struct Param(Arc<AtomicU32>);
+
+impl Param {
+	/// creates a parameter with the initial value
+	pub fn new(value: f32) -> Self {
+		Param(Arc::new(AtomicU32::new(value.to_bits())))
+	}
+
+	/// sets the value, which is used by the synth from the next rendered sample on
+	pub fn set(&self, value: f32) {
+		self.0.store(value.to_bits(), Ordering::Relaxed);
+	}
+
+	/// returns the current value
+	pub fn get(&self) -> f32 {
+		f32::from_bits(self.0.load(Ordering::Relaxed))
+	}
+}
+
+
+/// This struct contains the settings of an ADSR envelope.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Envelope {
+	/// time in s to rise from 0.0 to 1.0 after note_on()
+	pub attack: f32,
+	/// time in s to fall from 1.0 to the sustain level
+	pub decay: f32,
+	/// level in [0.0, 1.0] held until note_off()
+	pub sustain: f32,
+	/// time in s to fall from the sustain level to 0.0 after note_off()
+	pub release: f32,
+}
+
+impl Envelope {
+	/// creates an envelope with the attack, decay and release times in s and the sustain level in [0.0, 1.0]
+	pub fn new(attack: f32, decay: f32, sustain: f32, release: f32) -> Self {
+		Envelope { attack, decay, sustain, release }
+	}
+}
+
+impl Default for Envelope {
+	/// the level is 1.0 while the note is on and 0.0 otherwise
+	fn default() -> Self {
+		Envelope::new(0.0, 0.0, 1.0, 0.0)
+	}
+}
+
+
+/// parameters of a voice shared between the sketch and the audio callback
+#[derive(Debug)]
+struct VoiceParams {
+	waveform: AtomicUsize,
+	frequency: Param,
+	amplitude: Param,
+	gate: AtomicBool,
+	attack: Param,
+	decay: Param,
+	sustain: Param,
+	release: Param,
+}
+
+/// This struct contains a voice of a synth, i.e. an oscillator with an ADSR envelope, see Synth::add_voice().
+///
+/// All settings are lock-free and can be changed from update() while the synth is playing. Clones control the same voice.
+#[derive(Clone, Debug)]
+pub struct Voice {
+	params: Arc<VoiceParams>,
+}
+
+impl Voice {
+	fn new(waveform: Waveform, frequency: f32) -> Self {
+		let envelope = Envelope::default();
+		Voice {
+			params: Arc::new(VoiceParams {
+				waveform: AtomicUsize::new(waveform as usize),
+				frequency: Param::new(frequency),
+				amplitude: Param::new(1.0),
+				gate: AtomicBool::new(false),
+				attack: Param::new(envelope.attack),
+				decay: Param::new(envelope.decay),
+				sustain: Param::new(envelope.sustain),
+				release: Param::new(envelope.release),
+			}),
+		}
+	}
+
+	pub fn set_waveform(&self, waveform: Waveform) {
+		self.params.waveform.store(waveform as usize, Ordering::Relaxed);
+	}
+
+	pub fn waveform(&self) -> Waveform {
+		Waveform::from_index(self.params.waveform.load(Ordering::Relaxed))
+	}
+
+	/// sets the frequency of the oscillator in Hz
+	pub fn set_frequency(&self, frequency: f32) {
+		self.params.frequency.set(frequency);
+	}
+
+	pub fn frequency(&self) -> f32 {
+		self.params.frequency.get()
+	}
+
+	/// sets the amplitude of the oscillator, default: 1.0
+	pub fn set_amplitude(&self, amplitude: f32) {
+		self.params.amplitude.set(amplitude);
+	}
+
+	pub fn amplitude(&self) -> f32 {
+		self.params.amplitude.get()
+	}
+
+	pub fn set_envelope(&self, envelope: Envelope) {
+		self.params.attack.set(envelope.attack);
+		self.params.decay.set(envelope.decay);
+		self.params.sustain.set(envelope.sustain);
+		self.params.release.set(envelope.release);
+	}
+
+	pub fn envelope(&self) -> Envelope {
+		Envelope::new(self.params.attack.get(), self.params.decay.get(), self.params.sustain.get(), self.params.release.get())
+	}
+
+	/// starts the attack phase of the envelope (a voice is silent until note_on() is called)
+	pub fn note_on(&self) {
+		self.params.gate.store(true, Ordering::Relaxed);
+	}
+
+	/// starts the release phase of the envelope
+	pub fn note_off(&self) {
+		self.params.gate.store(false, Ordering::Relaxed);
+	}
+
+	pub fn is_on(&self) -> bool {
+		self.params.gate.load(Ordering::Relaxed)
+	}
+}
+
+
+/// phases of an ADSR envelope
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Stage {
+	Idle,
+	Attack,
+	Decay,
+	Sustain,
+	Release,
+}
+
+/// state of a voice, which is only used by the audio callback
+struct VoiceState {
+	voice: Voice,
+	phase: f64, // in [0.0, 1.0)
+	stage: Stage,
+	level: f32, // of the envelope
+	release_level: f32, // level at the start of the release phase
+	noise: u32, // state of the xorshift noise generator
+}
+
+impl VoiceState {
+	fn next_sample(&mut self, dt: f32) -> f32 {
+		let level = self.next_level(dt);
+		if self.stage == Stage::Idle {
+			return 0.0;
+		}
+		let value = match self.voice.waveform() {
+			Waveform::SINE => (2.0 * PI * self.phase).sin() as f32,
+			Waveform::SQUARE => if self.phase < 0.5 { 1.0 } else { -1.0 },
+			Waveform::SAW => (2.0 * self.phase - 1.0) as f32,
+			Waveform::TRIANGLE => (1.0 - 4.0 * (self.phase - 0.5).abs()) as f32,
+			Waveform::NOISE => {
+				self.noise ^= self.noise << 13;
+				self.noise ^= self.noise >> 17;
+				self.noise ^= self.noise << 5;
+				self.noise as f32 / u32::MAX as f32 * 2.0 - 1.0
+			},
+		};
+		self.phase = (self.phase + self.voice.frequency() as f64 * dt as f64).fract();
+		value * level * self.voice.amplitude()
+	}
+
+	/// advances the envelope by dt seconds and returns its level
+	fn next_level(&mut self, dt: f32) -> f32 {
+		let envelope = self.voice.envelope();
+		let gate = self.voice.is_on();
+		if gate && (self.stage == Stage::Idle || self.stage == Stage::Release) {
+			self.stage = Stage::Attack;
+		} else if !gate && self.stage != Stage::Idle && self.stage != Stage::Release {
+			self.stage = Stage::Release;
+			self.release_level = self.level;
+		}
+
+		match self.stage {
+			Stage::Idle => {
+				self.level = 0.0;
+				self.phase = 0.0;
+			},
+			Stage::Attack => {
+				self.level = if envelope.attack > 0.0 { self.level + dt / envelope.attack } else { 1.0 };
+				if self.level >= 1.0 {
+					self.level = 1.0;
+					self.stage = Stage::Decay;
+				}
+			},
+			Stage::Decay => {
+				self.level = if envelope.decay > 0.0 { self.level - dt * (1.0 - envelope.sustain) / envelope.decay } else { envelope.sustain };
+				if self.level <= envelope.sustain {
+					self.level = envelope.sustain;
+					self.stage = Stage::Sustain;
+				}
+			},
+			Stage::Sustain => {
+				self.level = envelope.sustain;
+			},
+			Stage::Release => {
+				self.level = if envelope.release > 0.0 { self.level - dt * self.release_level / envelope.release } else { 0.0 };
+				if self.level <= 0.0 {
+					self.level = 0.0;
+					self.stage = Stage::Idle;
+				}
+			},
+		}
+		self.level
+	}
+}
+
+
+/// This struct contains a synthesizer, which generates mono audio from a number of voices.
+///
+/// The voices are added before the synth is played with Sketch::play_synth() or rendered offline with render() or render_to_wav(). Afterwards the voices and the gain are controlled via the returned handles.
+pub struct Synth {
+	voices: Vec<VoiceState>,
+	gain: Param,
+	sample_rate: i32,
+	analyzers: Vec<AnalyzerInput>,
+}
+
+impl Synth {
+	pub fn new() -> Self {
+		Synth {
+			voices: Vec::new(),
+			gain: Param::new(1.0),
+			sample_rate: DEFAULT_SAMPLE_RATE,
+			analyzers: Vec::new(),
+		}
+	}
+
+	/// adds a voice with the given waveform and frequency in Hz and returns a handle to control it
+	pub fn add_voice(&mut self, waveform: Waveform, frequency: f32) -> Voice {
+		let voice = Voice::new(waveform, frequency);
+		self.voices.push(VoiceState {
+			voice: voice.clone(),
+			phase: 0.0,
+			stage: Stage::Idle,
+			level: 0.0,
+			release_level: 0.0,
+			noise: 0x2545_f491 ^ (self.voices.len() as u32 + 1).wrapping_mul(0x9e37_79b9),
+		});
+		voice
+	}
+
+	/// returns a handle to the gain, which is multiplied with the sum of all voices (default: 1.0)
+	pub fn gain(&self) -> Param {
+		self.gain.clone()
+	}
+
+	/// feeds the rendered samples into the analyzer
+	///
+	/// While the synth is played, samples rendered while the analyzer is busy are dropped, so the audio callback never waits (see AnalyzerInput::push_samples()).
+	pub fn attach_analyzer(&mut self, analyzer: &mut Analyzer) {
+		let input = analyzer.input();
+		input.set_sample_rate(self.sample_rate);
+		self.analyzers.push(input);
+	}
+
+	/// sets the sample rate in Hz used by render() and render_to_wav()
+	///
+	/// Sketch::play_synth() sets the sample rate of the audio device. The sample rate of attached analyzers is changed, too.
+	pub fn set_sample_rate(&mut self, sample_rate: i32) {
+		self.sample_rate = sample_rate;
+		for analyzer in &self.analyzers {
+			analyzer.set_sample_rate(sample_rate);
+		}
+	}
+
+	pub fn sample_rate(&self) -> i32 {
+		self.sample_rate
+	}
+
+	/// fills the buffer with the next samples
+	pub fn render(&mut self, out: &mut [f32]) {
+		let dt = 1.0 / self.sample_rate as f32;
+		let gain = self.gain.get();
+		for sample in out.iter_mut() {
+			let sum: f32 = self.voices.iter_mut().map( |v| v.next_sample(dt) ).sum();
+			*sample = sum * gain;
+		}
+		for analyzer in &self.analyzers {
+			analyzer.push_samples(out);
+		}
+	}
+
+	/// renders the given duration in s to a 16 bit mono WAV file
+	pub fn render_to_wav(&mut self, path: &Path, duration: f32) -> Result<(), String> {
+		let mut samples = vec![0.0; (duration * self.sample_rate as f32).round() as usize];
+		self.render(&mut samples);
+		write_wav(path, &samples, self.sample_rate)
+	}
+}
+
+impl Default for Synth {
+	fn default() -> Self {
+		Synth::new()
+	}
+}
+
+impl AudioCallback for Synth {
+	type Channel = f32;
+
+	fn callback(&mut self, out: &mut [f32]) {
+		self.render(out);
+	}
+}
+
+
+/// writes mono samples in [-1.0, 1.0] to a 16 bit PCM WAV file (samples out of range are clipped)
+pub fn write_wav(path: &Path, samples: &[f32], sample_rate: i32) -> Result<(), String> {
+	let data_len = samples.len() as u32 * 2;
+	let mut header = Vec::with_capacity(44);
+	header.extend_from_slice(b"RIFF");
+	header.extend_from_slice(&(36 + data_len).to_le_bytes());
+	header.extend_from_slice(b"WAVEfmt ");
+	header.extend_from_slice(&16u32.to_le_bytes()); // size of the format chunk
+	header.extend_from_slice(&1u16.to_le_bytes()); // PCM
+	header.extend_from_slice(&1u16.to_le_bytes()); // mono
+	header.extend_from_slice(&(sample_rate as u32).to_le_bytes());
+	header.extend_from_slice(&(sample_rate as u32 * 2).to_le_bytes()); // bytes per second
+	header.extend_from_slice(&2u16.to_le_bytes()); // bytes per sample
+	header.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+	header.extend_from_slice(b"data");
+	header.extend_from_slice(&data_len.to_le_bytes());
+
+	let file = File::create(path).map_err( |e| format!("Creation of {} failed. {}", path.display(), e) )?;
+	let mut writer = BufWriter::new(file);
+	let mut write = || -> std::io::Result<()> {
+		writer.write_all(&header)?;
+		for sample in samples {
+			let value = (::utils::constrain(*sample, -1.0, 1.0) * i16::MAX as f32) as i16;
+			writer.write_all(&value.to_le_bytes())?;
+		}
+		writer.flush()
+	};
+	write().map_err( |e| format!("Writing of {} failed. {}", path.display(), e) )
+}
+
+
+impl Sketch {
+
+	/* synth */
+
+	/// plays the synth on the default audio device until stop_synth() is called
+	///
+	/// The synth is moved into the audio callback, so its voices and gain can only be controlled via the handles returned by Synth::add_voice() and Synth::gain(). Playing another synth stops the current one.
+	pub fn play_synth(&mut self, mut synth: Synth) -> Result<(), String> {
+		self.stop_synth();
+		let audio_subsystem = self._sdl_context.audio()?;
+		let spec = AudioSpecDesired {
+			freq: Some(synth.sample_rate),
+			channels: Some(1),
+			samples: Some(512),
+		};
+		let device = audio_subsystem.open_playback(None, &spec, |obtained| {
+			synth.set_sample_rate(obtained.freq);
+			synth
+		})?;
+		device.resume();
+		self.synth_device = Some(device);
+		Ok(())
+	}
+
+	/// stops the synth played with play_synth()
+	pub fn stop_synth(&mut self) {
+		self.synth_device = None;
+	}
+
+	pub fn synth_is_playing(&self) -> bool {
+		self.synth_device.is_some()
+	}
+}
@@ -0,0 +1,203 @@
+use std::env;
+use std::fs;
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
+use sdl2::image::{LoadSurface, SaveSurface};
+
+use {Sketch, MainLoopMethods, ClockMode};
+
+/// pixel format of all compared images (byte order R, G, B, A on little endian systems)
+const PIXEL_FORMAT: PixelFormatEnum = PixelFormatEnum::ABGR8888;
+const BYTES_PER_PIXEL: usize = 4;
+
+// SDL can only be initialized once at a time, so headless sketches must not be created in parallel
+static SDL_LOCK: Mutex<()> = Mutex::new(());
+
+
+/// This struct contains a sketch which runs with SDL's dummy video driver, see headless_sketch() and run_headless().
+///
+/// It dereferences to the sketch, so all methods of Sketch can be used.
+pub struct HeadlessSketch {
+	sketch: Sketch,
+	_lock: MutexGuard<'static, ()>, // dropped after the sketch
+}
+
+impl Deref for HeadlessSketch {
+	type Target = Sketch;
+
+	fn deref(&self) -> &Sketch {
+		&self.sketch
+	}
+}
+
+impl DerefMut for HeadlessSketch {
+	fn deref_mut(&mut self) -> &mut Sketch {
+		&mut self.sketch
+	}
+}
+
+
+/// This struct contains the tolerance of the comparison of a canvas with a reference image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+	/// max. difference of each color channel of a pixel, which is still considered equal
+	pub channel: u8,
+	/// max. ratio of differing pixels in [0.0, 1.0]
+	pub max_ratio: f32,
+}
+
+impl Tolerance {
+	/// creates a tolerance of channel per color channel and max_ratio of differing pixels, e.g. Tolerance::new(8, 0.01) allows 1% of the pixels to differ by more than 8
+	pub fn new(channel: u8, max_ratio: f32) -> Self {
+		Tolerance { channel, max_ratio }
+	}
+
+	/// no differences are allowed at all
+	pub fn exact() -> Self {
+		Tolerance::new(0, 0.0)
+	}
+}
+
+
+/// This struct contains the result of the comparison of two images.
+pub struct ImageDiff {
+	/// number of pixels with at least one channel exceeding the tolerance
+	pub differing_pixels: usize,
+	/// number of compared pixels
+	pub total_pixels: usize,
+	/// RGBA image with the differing pixels in red and a faded copy of the expected image everywhere else
+	pub image: Vec<u8>,
+}
+
+impl ImageDiff {
+	/// returns the ratio of differing pixels in [0.0, 1.0]
+	pub fn ratio(&self) -> f32 {
+		if self.total_pixels == 0 {
+			return 0.0;
+		}
+		self.differing_pixels as f32 / self.total_pixels as f32
+	}
+}
+
+
+/// creates a sketch with SDL's dummy video and audio drivers for tests, which call Sketch::step() or sdl2sketch::run() themselves
+///
+/// The framerate is set to 200 fps, so tests do not wait longer than necessary.
+/// Only one sketch can exist at a time, so the returned sketch holds a lock until it is dropped, i.e. tests using headless sketches never run in parallel.
+pub fn headless_sketch(width: u32, height: u32) -> HeadlessSketch {
+	let lock = SDL_LOCK.lock().unwrap_or_else( |e| e.into_inner() );
+	env::set_var("SDL_VIDEODRIVER", "dummy");
+	env::set_var("SDL_AUDIODRIVER", "dummy");
+	let mut sketch = Sketch::new(width, height, "sdl2sketch headless");
+	sketch.set_framerate(200);
+	HeadlessSketch { sketch, _lock: lock }
+}
+
+/// creates a sketch with SDL's dummy video driver (see headless_sketch()) and runs n frames of the main loop
+///
+/// The sketch uses the virtual clock (see Sketch::clock_mode()), so animations look the same in every run.
+pub fn run_headless<T: MainLoopMethods>(width: u32, height: u32, m: &mut T, frames: u32) -> HeadlessSketch {
+	let mut s = headless_sketch(width, height);
+	s.clock_mode(ClockMode::VIRTUAL);
+	s.run_frames(m, frames);
+	s
+}
+
+/// compares two RGBA images of the same size pixel by pixel
+///
+/// A pixel differs if the difference of any color channel exceeds the channel tolerance.
+pub fn compare_pixels(actual: &[u8], expected: &[u8], channel_tolerance: u8) -> ImageDiff {
+	let mut diff = ImageDiff {
+		differing_pixels: 0,
+		total_pixels: actual.len().min(expected.len()) / BYTES_PER_PIXEL,
+		image: Vec::with_capacity(expected.len()),
+	};
+	for (a, e) in actual.chunks(BYTES_PER_PIXEL).zip(expected.chunks(BYTES_PER_PIXEL)) {
+		let differs = a.iter().zip(e.iter()).any( |(ca, ce)| (*ca as i16 - *ce as i16).abs() > channel_tolerance as i16 );
+		if differs {
+			diff.differing_pixels += 1;
+			diff.image.extend_from_slice(&[255, 0, 0, 255]);
+		} else {
+			diff.image.extend_from_slice(&[e[0] / 3, e[1] / 3, e[2] / 3, 255]);
+		}
+	}
+	diff
+}
+
+/// compares the canvas of the sketch with a reference PNG image
+///
+/// If the comparison fails, the canvas is written to <name>.actual.png and the differences to <name>.diff.png next to the reference image.
+/// A missing reference image is an error, too. If the environment variable SDL2SKETCH_UPDATE_SNAPSHOTS is set, the canvas is written to the reference image instead of comparing it.
+pub fn check_snapshot(s: &Sketch, reference: &Path, tolerance: Tolerance) -> Result<(), String> {
+	let (width, height) = s.canvas.output_size()?;
+	let mut actual = s.canvas.read_pixels(None, PIXEL_FORMAT)?;
+
+	if env::var("SDL2SKETCH_UPDATE_SNAPSHOTS").is_ok() {
+		if let Some(dir) = reference.parent() {
+			fs::create_dir_all(dir).map_err( |e| e.to_string() )?;
+		}
+		save_png(&mut actual, width, height, reference)?;
+		eprintln!("Reference image {} written.", reference.display());
+		return Ok(());
+	}
+
+	let actual_path = reference.with_extension("actual.png");
+	if !reference.exists() {
+		if let Some(dir) = reference.parent() {
+			fs::create_dir_all(dir).map_err( |e| e.to_string() )?;
+		}
+		save_png(&mut actual, width, height, &actual_path)?;
+		return Err(format!("Reference image {} does not exist, see {}. Set SDL2SKETCH_UPDATE_SNAPSHOTS to write it.",
+			reference.display(), actual_path.display()));
+	}
+	let (ref_width, ref_height, expected) = load_png(reference)?;
+	if (ref_width, ref_height) != (width, height) {
+		save_png(&mut actual, width, height, &actual_path)?;
+		return Err(format!("Size of canvas ({}x{}) differs from reference image {} ({}x{}), see {}.",
+			width, height, reference.display(), ref_width, ref_height, actual_path.display()));
+	}
+
+	let mut diff = compare_pixels(&actual, &expected, tolerance.channel);
+	if diff.ratio() > tolerance.max_ratio {
+		let diff_path = reference.with_extension("diff.png");
+		save_png(&mut actual, width, height, &actual_path)?;
+		save_png(&mut diff.image, width, height, &diff_path)?;
+		return Err(format!("{} of {} pixels ({:.2}%) differ from reference image {}, see {} and {}.",
+			diff.differing_pixels, diff.total_pixels, diff.ratio() * 100.0, reference.display(), actual_path.display(), diff_path.display()));
+	}
+	Ok(())
+}
+
+/// compares the canvas of the sketch with a reference PNG image and panics if they differ (see check_snapshot())
+pub fn assert_snapshot(s: &Sketch, reference: &Path, tolerance: Tolerance) {
+	if let Err(e) = check_snapshot(s, reference, tolerance) {
+		panic!("Snapshot test failed. {}", e);
+	}
+}
+
+/// writes RGBA pixels to a PNG file
+fn save_png(pixels: &mut [u8], width: u32, height: u32, path: &Path) -> Result<(), String> {
+	let surface = Surface::from_data(pixels, width, height, width * BYTES_PER_PIXEL as u32, PIXEL_FORMAT)?;
+	surface.save(path)
+}
+
+/// reads an image file and returns its size and RGBA pixels
+fn load_png(path: &Path) -> Result<(u32, u32, Vec<u8>), String> {
+	let loaded = Surface::from_file(path)?;
+	let target = Surface::new(1, 1, PIXEL_FORMAT)?;
+	let surface = loaded.convert(&target.pixel_format())?;
+	let (width, height) = surface.size();
+	let pitch = surface.pitch() as usize;
+	let row_len = width as usize * BYTES_PER_PIXEL;
+	let pixels = surface.with_lock( |data| {
+		let mut pixels = Vec::with_capacity(row_len * height as usize);
+		for row in 0..height as usize {
+			pixels.extend_from_slice(&data[row * pitch..row * pitch + row_len]);
+		}
+		pixels
+	});
+	Ok((width, height, pixels))
+}
@@ -1,5 +1,6 @@
 extern crate num_traits;
 use num_traits::Float;
+use rand::Rng;
 
 use std::f32::consts::PI;
 const FTOL: f32 = 0.0001;
@@ -92,3 +93,254 @@ pub fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (u16, f32, f32) {
 
 	(hue as u16, sat, c_max)
 }
+
+
+/// utility function to choose an index randomly, the probability of each index is proportional to its weight
+///
+/// Negative weights count as 0. If there is no positive weight, None is returned.
+pub fn weighted_index<R: Rng>(rng: &mut R, weights: &[f32]) -> Option<usize> {
+	let total: f32 = weights.iter().filter( |w| **w > 0.0 ).sum();
+	if total <= 0.0 {
+		return None;
+	}
+	let mut r = rng.gen::<f32>() * total;
+	for (i, w) in weights.iter().enumerate() {
+		if *w > 0.0 {
+			if r < *w {
+				return Some(i);
+			}
+			r -= *w;
+		}
+	}
+	weights.iter().rposition( |w| *w > 0.0 ) // in case of rounding errors
+}
+
+
+/* noise */
+
+const PERLIN_YWRAPB: i32 = 4;
+const PERLIN_YWRAP: i32 = 1 << PERLIN_YWRAPB;
+const PERLIN_ZWRAPB: i32 = 8;
+const PERLIN_ZWRAP: i32 = 1 << PERLIN_ZWRAPB;
+const PERLIN_SIZE: i32 = 4095;
+
+/// linear congruential generator as used by noiseSeed() of p5.js
+struct Lcg(u32);
+
+impl Lcg {
+	/// returns a random number in [0, 1)
+	fn next(&mut self) -> f64 {
+		self.0 = self.0.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+		self.0 as f64 / 4_294_967_296.0
+	}
+}
+
+fn scaled_cosine(i: f32) -> f32 {
+	0.5 * (1.0 - (i * PI).cos())
+}
+
+/// This struct contains the state of the Perlin noise of p5.js, see noise().
+///
+/// For the same seed and detail, the values are the same as those of p5.js (apart from the precision of f32).
+pub struct Noise {
+	perlin: Vec<f32>,
+	octaves: u32,
+	falloff: f32,
+}
+
+impl Noise {
+	/// creates Perlin noise with 4 octaves and a falloff of 0.5
+	pub fn new(seed: u32) -> Self {
+		let mut noise = Noise { perlin: Vec::new(), octaves: 4, falloff: 0.5 };
+		noise.noise_seed(seed);
+		noise
+	}
+
+	/// sets the seed, the same seed always results in the same noise
+	pub fn noise_seed(&mut self, seed: u32) {
+		let mut lcg = Lcg(seed);
+		self.perlin = (0..=PERLIN_SIZE).map( |_| lcg.next() as f32 ).collect();
+	}
+
+	/// sets the number of octaves and the falloff factor of the amplitude of each octave (values of 0 are ignored)
+	///
+	/// With a falloff greater than 0.5 the noise can exceed 1.0.
+	pub fn noise_detail(&mut self, octaves: u32, falloff: f32) {
+		if octaves > 0 {
+			self.octaves = octaves;
+		}
+		if falloff > 0.0 {
+			self.falloff = falloff;
+		}
+	}
+
+	/// returns 1D Perlin noise in [0.0, 1.0)
+	pub fn noise(&self, x: f32) -> f32 {
+		self.noise3(x, 0.0, 0.0)
+	}
+
+	/// returns 2D Perlin noise in [0.0, 1.0)
+	pub fn noise2(&self, x: f32, y: f32) -> f32 {
+		self.noise3(x, y, 0.0)
+	}
+
+	/// returns 3D Perlin noise in [0.0, 1.0)
+	pub fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+		let perlin = |i: i32| self.perlin[(i & PERLIN_SIZE) as usize];
+		let (x, y, z) = (x.abs(), y.abs(), z.abs());
+		let (mut xi, mut yi, mut zi) = (x.floor() as i32, y.floor() as i32, z.floor() as i32);
+		let (mut xf, mut yf, mut zf) = (x - xi as f32, y - yi as f32, z - zi as f32);
+		let mut r = 0.0;
+		let mut ampl = 0.5;
+
+		for _ in 0..self.octaves {
+			let mut of = xi.wrapping_add(yi.wrapping_shl(PERLIN_YWRAPB as u32)).wrapping_add(zi.wrapping_shl(PERLIN_ZWRAPB as u32));
+			let rxf = scaled_cosine(xf);
+			let ryf = scaled_cosine(yf);
+
+			let mut n1 = perlin(of);
+			n1 += rxf * (perlin(of.wrapping_add(1)) - n1);
+			let mut n2 = perlin(of.wrapping_add(PERLIN_YWRAP));
+			n2 += rxf * (perlin(of.wrapping_add(PERLIN_YWRAP + 1)) - n2);
+			n1 += ryf * (n2 - n1);
+
+			of = of.wrapping_add(PERLIN_ZWRAP);
+			n2 = perlin(of);
+			n2 += rxf * (perlin(of.wrapping_add(1)) - n2);
+			let mut n3 = perlin(of.wrapping_add(PERLIN_YWRAP));
+			n3 += rxf * (perlin(of.wrapping_add(PERLIN_YWRAP + 1)) - n3);
+			n2 += ryf * (n3 - n2);
+			n1 += scaled_cosine(zf) * (n2 - n1);
+
+			r += n1 * ampl;
+			ampl *= self.falloff;
+
+			xi = xi.wrapping_shl(1);
+			yi = yi.wrapping_shl(1);
+			zi = zi.wrapping_shl(1);
+			xf *= 2.0;
+			yf *= 2.0;
+			zf *= 2.0;
+			if xf >= 1.0 { xi = xi.wrapping_add(1); xf -= 1.0; }
+			if yf >= 1.0 { yi = yi.wrapping_add(1); yf -= 1.0; }
+			if zf >= 1.0 { zi = zi.wrapping_add(1); zf -= 1.0; }
+		}
+		r
+	}
+}
+
+
+// skewing factors of 4D simplex noise
+const F4: f32 = 0.309_017; // (sqrt(5) - 1) / 4
+const G4: f32 = 0.138_196_6; // (5 - sqrt(5)) / 20
+
+const GRAD4: [[f32; 4]; 32] = [
+	[0.0, 1.0, 1.0, 1.0], [0.0, 1.0, 1.0, -1.0], [0.0, 1.0, -1.0, 1.0], [0.0, 1.0, -1.0, -1.0],
+	[0.0, -1.0, 1.0, 1.0], [0.0, -1.0, 1.0, -1.0], [0.0, -1.0, -1.0, 1.0], [0.0, -1.0, -1.0, -1.0],
+	[1.0, 0.0, 1.0, 1.0], [1.0, 0.0, 1.0, -1.0], [1.0, 0.0, -1.0, 1.0], [1.0, 0.0, -1.0, -1.0],
+	[-1.0, 0.0, 1.0, 1.0], [-1.0, 0.0, 1.0, -1.0], [-1.0, 0.0, -1.0, 1.0], [-1.0, 0.0, -1.0, -1.0],
+	[1.0, 1.0, 0.0, 1.0], [1.0, 1.0, 0.0, -1.0], [1.0, -1.0, 0.0, 1.0], [1.0, -1.0, 0.0, -1.0],
+	[-1.0, 1.0, 0.0, 1.0], [-1.0, 1.0, 0.0, -1.0], [-1.0, -1.0, 0.0, 1.0], [-1.0, -1.0, 0.0, -1.0],
+	[1.0, 1.0, 1.0, 0.0], [1.0, 1.0, -1.0, 0.0], [1.0, -1.0, 1.0, 0.0], [1.0, -1.0, -1.0, 0.0],
+	[-1.0, 1.0, 1.0, 0.0], [-1.0, 1.0, -1.0, 0.0], [-1.0, -1.0, 1.0, 0.0], [-1.0, -1.0, -1.0, 0.0],
+];
+
+/// This struct contains the permutation table of 4D simplex noise.
+///
+/// Source of algorithm: Stefan Gustavson, "Simplex noise demystified" (2005)
+pub struct SimplexNoise {
+	perm: [u8; 512],
+}
+
+impl SimplexNoise {
+	/// creates simplex noise, the same seed always results in the same noise
+	pub fn new(seed: u32) -> Self {
+		let mut table: Vec<u8> = (0..=255).collect();
+		let mut lcg = Lcg(seed);
+		for i in (1..table.len()).rev() {
+			let j = (lcg.next() * (i + 1) as f64) as usize;
+			table.swap(i, j);
+		}
+		let mut perm = [0; 512];
+		for (i, p) in perm.iter_mut().enumerate() {
+			*p = table[i & 255];
+		}
+		SimplexNoise { perm }
+	}
+
+	/// returns 4D simplex noise in [-1.0, 1.0]
+	///
+	/// The 4th dimension can be used as time to animate 3D noise, or to create seamlessly looping 2D noise by moving on two circles.
+	pub fn noise4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+		// skew the input space to find the simplex cell
+		let s = (x + y + z + w) * F4;
+		let (i, j, k, l) = ((x + s).floor(), (y + s).floor(), (z + s).floor(), (w + s).floor());
+		let t = (i + j + k + l) * G4;
+		let x0 = [x - (i - t), y - (j - t), z - (k - t), w - (l - t)];
+
+		// rank the coordinates to find the simplex (one of 24) containing the point
+		let mut rank = [0; 4];
+		for a in 0..4 {
+			for b in a + 1..4 {
+				if x0[a] > x0[b] { rank[a] += 1; } else { rank[b] += 1; }
+			}
+		}
+
+		let cell = [i as i32 & 255, j as i32 & 255, k as i32 & 255, l as i32 & 255];
+		let mut n = 0.0;
+		for corner in 0..5 {
+			// offsets of the corner in lattice coordinates and in the unskewed space
+			let mut offset = [0; 4];
+			let mut d = [0.0; 4];
+			for a in 0..4 {
+				offset[a] = (rank[a] >= 4 - corner) as usize;
+				d[a] = x0[a] - offset[a] as f32 + corner as f32 * G4;
+			}
+			let t = 0.6 - d.iter().map( |v| v * v ).sum::<f32>();
+			if t > 0.0 {
+				let p = |a: usize, v: usize| self.perm[(cell[a] as usize + offset[a]) + v] as usize;
+				let gi = p(0, p(1, p(2, p(3, 0)))) % 32;
+				let g = GRAD4[gi];
+				n += t * t * t * t * (g[0] * d[0] + g[1] * d[1] + g[2] * d[2] + g[3] * d[3]);
+			}
+		}
+		constrain(27.0 * n, -1.0, 1.0)
+	}
+}
+
+
+/// utility function for fractal Brownian motion: sums octaves of noise with increasing frequency and decreasing amplitude
+///
+/// The closure returns noise in [-1.0, 1.0] for a given frequency (e.g. |f| simplex.noise4(x * f, y * f, z * f, w * f)). The frequency is multiplied by the lacunarity and the amplitude by the gain for each octave. The result is normalized to [-1.0, 1.0].
+pub fn fbm<F: FnMut(f32) -> f32>(octaves: u32, lacunarity: f32, gain: f32, noise: F) -> f32 {
+	fractal(octaves, lacunarity, gain, noise, |n| n)
+}
+
+/// utility function for ridged multifractal noise: like fbm(), but sharp ridges where the noise crosses zero
+///
+/// The result is normalized to [0.0, 1.0].
+pub fn ridged<F: FnMut(f32) -> f32>(octaves: u32, lacunarity: f32, gain: f32, noise: F) -> f32 {
+	fractal(octaves, lacunarity, gain, noise, |n| (1.0 - n.abs()).powi(2))
+}
+
+/// utility function for turbulence: like fbm(), but sums the absolute values of the noise
+///
+/// The result is normalized to [0.0, 1.0].
+pub fn turbulence<F: FnMut(f32) -> f32>(octaves: u32, lacunarity: f32, gain: f32, noise: F) -> f32 {
+	fractal(octaves, lacunarity, gain, noise, |n| n.abs())
+}
+
+/// sums octaves of noise transformed by a function and normalizes the sum
+fn fractal<F: FnMut(f32) -> f32, T: Fn(f32) -> f32>(octaves: u32, lacunarity: f32, gain: f32, mut noise: F, transform: T) -> f32 {
+	let mut sum = 0.0;
+	let mut total = 0.0;
+	let mut frequency = 1.0;
+	let mut amplitude = 1.0;
+	for _ in 0..octaves {
+		sum += amplitude * transform(noise(frequency));
+		total += amplitude;
+		frequency *= lacunarity;
+		amplitude *= gain;
+	}
+	if total > 0.0 { sum / total } else { 0.0 }
+}
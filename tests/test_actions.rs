@@ -0,0 +1,140 @@
+extern crate sdl2sketch;
+use sdl2sketch::*;
+use sdl2sketch::testing;
+
+use std::{env, fs};
+
+struct Empty;
+
+impl MainLoopMethods for Empty {}
+
+#[test]
+fn test_binding_parse() {
+	assert_eq!("key:Space".parse::<Binding>(), Ok(Binding::Key(Keycode::Space)));
+	assert_eq!("key:Left Shift".parse::<Binding>(), Ok(Binding::Key(Keycode::LShift)));
+	assert_eq!("scancode:W".parse::<Binding>(), Ok(Binding::Scancode(Scancode::W)));
+	assert_eq!("mouse:Right".parse::<Binding>(), Ok(Binding::MouseButton(MouseButton::Right)));
+	assert!("key:NoSuchKey".parse::<Binding>().is_err());
+	assert!("joystick:1".parse::<Binding>().is_err());
+	assert!("Space".parse::<Binding>().is_err());
+}
+
+#[test]
+fn test_axis_binding_parse() {
+	assert_eq!("negative key:Left".parse::<AxisBinding>(), Ok(AxisBinding::Negative(Binding::Key(Keycode::Left))));
+	assert_eq!("positive scancode:D".parse::<AxisBinding>(), Ok(AxisBinding::Positive(Binding::Scancode(Scancode::D))));
+	assert!("key:Left".parse::<AxisBinding>().is_err());
+}
+
+#[test]
+fn test_binding_roundtrip() {
+	let bindings = [
+		Binding::Key(Keycode::Space),
+		Binding::Key(Keycode::Semicolon),
+		Binding::Scancode(Scancode::LCtrl),
+		Binding::MouseButton(MouseButton::X1),
+	];
+	for b in &bindings {
+		assert_eq!(b.to_string().parse::<Binding>().as_ref(), Ok(b));
+	}
+	let axis_binding = AxisBinding::Negative(Binding::Key(Keycode::A));
+	assert_eq!(axis_binding.to_string().parse::<AxisBinding>(), Ok(axis_binding));
+}
+
+#[test]
+fn test_actions_and_axes() {
+	let mut s = testing::headless_sketch(320, 240);
+	s.bind_action("jump", Binding::Key(Keycode::Space));
+	s.bind_action("jump", Binding::MouseButton(MouseButton::Left));
+	s.bind_axis("move_x", AxisBinding::Negative(Binding::Key(Keycode::Left)));
+	s.bind_axis("move_x", AxisBinding::Positive(Binding::Key(Keycode::Right)));
+	assert!(!s.action_down("jump"));
+	assert_eq!(s.axis("move_x"), 0.0);
+
+	s.push_mouse_button(MouseButton::Left, true, 1);
+	s.push_key(Keycode::Left, true);
+	s.run_frames(&mut Empty, 1);
+	assert!(s.action_down("jump"));
+	assert_eq!(s.axis("move_x"), -1.0);
+	assert!(!s.action_down("no_such_action"));
+	assert_eq!(s.axis("no_such_axis"), 0.0);
+
+	s.push_key(Keycode::Right, true);
+	s.push_mouse_button(MouseButton::Left, false, 1);
+	s.run_frames(&mut Empty, 1);
+	assert!(!s.action_down("jump"));
+	assert_eq!(s.axis("move_x"), 0.0); // both directions cancel out
+
+	s.push_key(Keycode::Left, false);
+	s.push_key(Keycode::Space, true);
+	s.run_frames(&mut Empty, 1);
+	assert!(s.action_down("jump"));
+	assert_eq!(s.axis("move_x"), 1.0);
+}
+
+#[test]
+fn test_save_and_load_bindings() {
+	let path = env::temp_dir().join("sdl2sketch_test_bindings.txt");
+	let mut s = testing::headless_sketch(320, 240);
+	s.bind_action("jump", Binding::Scancode(Scancode::Space));
+	s.bind_axis("move_x", AxisBinding::Positive(Binding::Key(Keycode::D)));
+	s.save_bindings(&path).unwrap();
+	let text = fs::read_to_string(&path).unwrap();
+	assert!(text.contains("action jump = scancode:Space"), "{}", text);
+	assert!(text.contains("action quit = key:Escape"), "{}", text);
+
+	s.unbind_action("jump");
+	s.unbind_axis("move_x");
+	s.load_bindings(&path).unwrap();
+	s.push_key(Keycode::Space, true);
+	s.push_key(Keycode::D, true);
+	s.run_frames(&mut Empty, 1);
+	assert!(s.action_down("jump"));
+	assert_eq!(s.axis("move_x"), 1.0);
+
+	fs::write(&path, "action jump = key:Space\naction run key:R\n").unwrap();
+	let err = s.load_bindings(&path).unwrap_err();
+	assert!(err.contains("line 2"), "{}", err);
+	assert!(s.action_down("jump")); // the bindings are unchanged after an error
+	fs::remove_file(&path).unwrap();
+	assert!(s.load_bindings(&path).is_err());
+}
+
+#[test]
+fn test_save_and_load_binding_names() {
+	let path = env::temp_dir().join("sdl2sketch_test_binding_names.txt");
+	let mut s = testing::headless_sketch(320, 240);
+	s.bind_action("jump high", Binding::Key(Keycode::Space));
+	s.bind_action("a=b \"c\\", Binding::Key(Keycode::B));
+	s.bind_axis("move x", AxisBinding::Positive(Binding::Key(Keycode::D)));
+	s.save_bindings(&path).unwrap();
+	let text = fs::read_to_string(&path).unwrap();
+	assert!(text.contains("action \"jump high\" = key:Space"), "{}", text);
+
+	s.unbind_action("jump high");
+	s.unbind_action("a=b \"c\\");
+	s.unbind_axis("move x");
+	s.load_bindings(&path).unwrap();
+	fs::remove_file(&path).unwrap();
+	s.push_key(Keycode::Space, true);
+	s.push_key(Keycode::B, true);
+	s.push_key(Keycode::D, true);
+	s.run_frames(&mut Empty, 1);
+	assert!(s.action_down("jump high"));
+	assert!(s.action_down("a=b \"c\\"));
+	assert_eq!(s.axis("move x"), 1.0);
+}
+
+#[test]
+fn test_escape_quits() {
+	let mut s = testing::headless_sketch(320, 240);
+	s.push_key(Keycode::Escape, true);
+	s.push_key(Keycode::Escape, false);
+	assert!(!s.step(&mut Empty)); // the default binding of the action "quit"
+
+	drop(s);
+	let mut s = testing::headless_sketch(320, 240);
+	s.unbind_action("quit");
+	s.push_key(Keycode::Escape, true);
+	assert!(s.step(&mut Empty));
+}
@@ -0,0 +1,114 @@
+extern crate sdl2sketch;
+use sdl2sketch::analyzer::*;
+use sdl2sketch::synth::{Synth, Waveform, DEFAULT_SAMPLE_RATE};
+
+use std::f32::consts::PI;
+
+const RATE: f32 = 44_100.0;
+
+fn tone(frequency: f32, amplitude: f32, len: usize) -> Vec<f32> {
+	(0..len).map( |i| amplitude * (2.0 * PI * frequency * i as f32 / RATE).sin() ).collect()
+}
+
+fn peak_bin(spectrum: &[f32]) -> usize {
+	(0..spectrum.len()).fold(0, |max, i| if spectrum[i] > spectrum[max] { i } else { max } )
+}
+
+#[test]
+fn test_spectrum() {
+	let mut analyzer = Analyzer::new(1024, 0.0);
+	assert_eq!(analyzer.bin_frequency(1), RATE / 2048.0);
+	let frequency = analyzer.bin_frequency(64);
+	analyzer.push_samples(&tone(frequency, 1.0, 4096));
+
+	let spectrum = analyzer.spectrum().to_vec();
+	assert_eq!(spectrum.len(), 1024);
+	assert_eq!(peak_bin(&spectrum), 64);
+	assert!((spectrum[64] - 1.0).abs() < 0.02, "{}", spectrum[64]);
+	assert!(spectrum[..60].iter().chain(spectrum[69..].iter()).all( |x| *x < 0.01 ));
+}
+
+#[test]
+fn test_spectrum_of_two_tones() {
+	let mut analyzer = Analyzer::new(512, 0.0);
+	let samples: Vec<f32> = tone(440.0, 0.5, 1024).iter().zip(tone(3000.0, 0.25, 1024)).map( |(a, b)| a + b ).collect();
+	analyzer.push_samples(&samples);
+
+	let spectrum = analyzer.spectrum().to_vec();
+	let low = peak_bin(&spectrum[..40]);
+	let high = 40 + peak_bin(&spectrum[40..]);
+	let bin_width = analyzer.bin_frequency(1);
+	assert!((analyzer.bin_frequency(low) - 440.0).abs() <= bin_width);
+	assert!((analyzer.bin_frequency(high) - 3000.0).abs() <= bin_width);
+	assert!(spectrum[low] > 0.4 && spectrum[low] < 0.55);
+	assert!(spectrum[high] > 0.2 && spectrum[high] < 0.3);
+}
+
+#[test]
+fn test_smoothing() {
+	let mut analyzer = Analyzer::new(256, 0.5);
+	let frequency = analyzer.bin_frequency(16);
+	analyzer.push_samples(&tone(frequency, 1.0, 512));
+	assert!((analyzer.spectrum()[16] - 0.5).abs() < 0.02);
+	assert!((analyzer.spectrum()[16] - 0.75).abs() < 0.02);
+	assert!((analyzer.level() - 0.5 * 0.5f32.sqrt()).abs() < 0.01);
+
+	analyzer.push_samples(&[0.0; 512]);
+	assert!((analyzer.spectrum()[16] - 0.375).abs() < 0.02);
+	analyzer.set_smoothing(2.0);
+	assert_eq!(analyzer.smoothing(), 0.99);
+}
+
+#[test]
+fn test_level_and_waveform() {
+	let mut analyzer = Analyzer::new(64, 0.0);
+	assert_eq!(analyzer.level(), 0.0);
+	assert_eq!(analyzer.waveform(), vec![0.0; 128]);
+
+	let frequency = analyzer.bin_frequency(4); // 4 periods in the analyzed samples
+	analyzer.push_samples(&tone(frequency, 0.5, 1000));
+	assert!((analyzer.level() - 0.5 / 2.0f32.sqrt()).abs() < 0.01);
+	analyzer.input().push_samples(&[1.0, -1.0, 0.5]);
+	let waveform = analyzer.waveform();
+	assert_eq!(waveform.len(), 128);
+	assert_eq!(&waveform[125..], &[1.0, -1.0, 0.5]);
+
+	analyzer.set_bins(100);
+	assert_eq!(analyzer.bins(), 128);
+	let waveform = analyzer.waveform();
+	assert_eq!(waveform.len(), 256);
+	assert_eq!(&waveform[253..], &[1.0, -1.0, 0.5]);
+	analyzer.set_bins(5);
+	assert_eq!(analyzer.bins(), 16);
+	assert_eq!(analyzer.spectrum().len(), 16);
+}
+
+#[test]
+fn test_synth_analyzer() {
+	let mut analyzer = Analyzer::new(1024, 0.0);
+	let mut synth = Synth::new();
+	synth.set_sample_rate(22_050);
+	synth.attach_analyzer(&mut analyzer);
+	assert_eq!(analyzer.sample_rate(), 22_050);
+	synth.add_voice(Waveform::SQUARE, 1000.0).note_on();
+	let mut out = vec![0.0; 4096];
+	synth.render(&mut out);
+
+	assert!((analyzer.level() - 1.0).abs() < 0.01);
+	let spectrum = analyzer.spectrum().to_vec();
+	let peak = peak_bin(&spectrum);
+	assert!((analyzer.bin_frequency(peak) - 1000.0).abs() <= analyzer.bin_frequency(1));
+	let harmonic = peak_bin(&spectrum[peak + 10..]) + peak + 10; // 3rd harmonic of the square wave
+	assert!((analyzer.bin_frequency(harmonic) - 3000.0).abs() <= analyzer.bin_frequency(1));
+}
+
+#[test]
+fn test_synth_analyzer_sample_rate() {
+	let mut analyzer = Analyzer::new(16, 0.0);
+	let mut synth = Synth::new();
+	synth.attach_analyzer(&mut analyzer);
+	assert_eq!(analyzer.sample_rate(), DEFAULT_SAMPLE_RATE);
+	synth.set_sample_rate(48_000); // like Sketch::play_synth() does with the rate of the audio device
+	assert_eq!(analyzer.sample_rate(), 48_000);
+	assert_eq!(analyzer.bin_frequency(1), 1500.0);
+}
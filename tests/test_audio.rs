@@ -0,0 +1,135 @@
+#![cfg(feature = "audio")]
+
+extern crate sdl2sketch;
+use sdl2sketch::*;
+use sdl2sketch::testing;
+use sdl2sketch::analyzer::Analyzer;
+
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/// writes one second of a 440 Hz sine tone as 16 bit mono WAV file
+fn write_tone(name: &str) -> PathBuf {
+	let path = env::temp_dir().join(name);
+	let rate = 44_100u32;
+	let samples: Vec<i16> = (0..rate).map( |i| ((i as f32 * 440.0 / rate as f32 * 2.0 * std::f32::consts::PI).sin() * 8000.0) as i16 ).collect();
+	let data_len = samples.len() as u32 * 2;
+	let mut bytes = Vec::new();
+	bytes.extend_from_slice(b"RIFF");
+	bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+	bytes.extend_from_slice(b"WAVEfmt ");
+	bytes.extend_from_slice(&16u32.to_le_bytes());
+	bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+	bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+	bytes.extend_from_slice(&rate.to_le_bytes());
+	bytes.extend_from_slice(&(rate * 2).to_le_bytes());
+	bytes.extend_from_slice(&2u16.to_le_bytes());
+	bytes.extend_from_slice(&16u16.to_le_bytes());
+	bytes.extend_from_slice(b"data");
+	bytes.extend_from_slice(&data_len.to_le_bytes());
+	for sample in samples {
+		bytes.extend_from_slice(&sample.to_le_bytes());
+	}
+	File::create(&path).and_then( |mut f| f.write_all(&bytes) ).expect("Writing of WAV file failed.");
+	path
+}
+
+#[test]
+fn test_sounds() {
+	let mut s = testing::headless_sketch(320, 240);
+	let path = write_tone("sdl2sketch_test_sounds.wav");
+	let beep = s.load_sound(&path);
+	let other = s.load_sound(&path);
+	assert!(!s.sound_is_playing(&beep));
+
+	s.play_sound(&beep);
+	assert!(s.sound_is_playing(&beep));
+	assert!(!s.sound_is_playing(&other));
+	s.loop_sound(&other);
+	s.sound_volume(&beep, 0.5);
+	s.sound_pan(&beep, -1.0);
+	s.sound_pan(&other, 0.25);
+	s.master_volume(0.8);
+	assert!(s.sound_is_playing(&other));
+
+	s.stop_sound(&beep);
+	assert!(!s.sound_is_playing(&beep));
+	assert!(s.sound_is_playing(&other));
+	s.stop_sound(&other);
+	assert!(!s.sound_is_playing(&other));
+}
+
+#[test]
+fn test_music() {
+	let mut s = testing::headless_sketch(320, 240);
+	let path = write_tone("sdl2sketch_test_music.wav");
+	let music = s.load_music(&path).expect("Loading of music failed.");
+	s.music_volume(0.5);
+	assert!(!Music::is_playing());
+	s.play_music(&music, true);
+	assert!(Music::is_playing());
+	s.stop_music();
+	assert!(!Music::is_playing());
+}
+
+#[test]
+fn test_sound_channels() {
+	let mut s = testing::headless_sketch(320, 240);
+	let path = write_tone("sdl2sketch_test_sound_channels.wav");
+	let first = s.load_sound(&path);
+	let second = s.load_sound(&path);
+	s.sound_volume(&second, 0.5);
+	drop(second);
+	let third = s.load_sound(&path); // reuses the channel of the dropped sound
+	s.loop_sound(&first);
+	s.play_sound(&third);
+	assert!(s.sound_is_playing(&first));
+	assert!(s.sound_is_playing(&third));
+	for _ in 0..100 {
+		drop(s.load_sound(&path));
+	}
+	assert!(s.sound_is_playing(&first)); // loading sounds does not change the channels of playing sounds
+
+	drop(s);
+	let mut s = testing::headless_sketch(320, 240);
+	s.sound_volume(&first, 0.5); // the sound was loaded by another sketch, so its channel is not allocated
+}
+
+#[test]
+fn test_load_errors() {
+	let mut s = testing::headless_sketch(320, 240);
+	assert!(s.try_load_sound(Path::new("does_not_exist.wav")).is_err());
+	assert!(s.load_music(Path::new("does_not_exist.ogg")).is_err());
+}
+
+#[test]
+fn test_analyzer() {
+	let mut s = testing::headless_sketch(320, 240);
+	let path = write_tone("sdl2sketch_test_analyzer.wav");
+	let beep = s.load_sound(&path);
+	let samples = beep.samples();
+	let rate = s.audio_sample_rate();
+	assert!((samples.len() as i32 - rate).abs() <= rate / 100); // 1 s
+
+	let mut analyzer = Analyzer::new(1024, 0.0);
+	analyzer.set_sample_rate(rate);
+	analyzer.push_samples(&samples);
+	let spectrum = analyzer.spectrum().to_vec();
+	let peak = (0..spectrum.len()).fold(0, |max, i| if spectrum[i] > spectrum[max] { i } else { max } );
+	assert!((analyzer.bin_frequency(peak) - 440.0).abs() <= analyzer.bin_frequency(1));
+
+	let mut output = Analyzer::new(256, 0.0);
+	s.attach_analyzer(&mut output);
+	assert_eq!(output.sample_rate(), rate);
+	s.play_sound(&beep);
+	thread::sleep(Duration::from_millis(200));
+	s.detach_analyzer();
+	assert!(output.level() > 0.0);
+	s.attach_analyzer(&mut output);
+	s.attach_analyzer(&mut analyzer); // replaces the attached analyzer
+	s.detach_analyzer();
+}
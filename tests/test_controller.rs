@@ -0,0 +1,81 @@
+extern crate sdl2sketch;
+use sdl2sketch::*;
+use sdl2sketch::testing;
+
+#[derive(Default)]
+struct ControllerLog {
+	connected: Vec<i32>,
+	disconnected: Vec<i32>,
+	pressed: Vec<(i32, ControllerButton)>,
+	released: Vec<(i32, ControllerButton)>,
+	axes: Vec<(i32, ControllerAxis, f32)>,
+}
+
+impl MainLoopMethods for ControllerLog {
+	fn controller_connected(&mut self, _s: &mut Sketch, id: i32) {
+		self.connected.push(id);
+	}
+
+	fn controller_disconnected(&mut self, _s: &mut Sketch, id: i32) {
+		self.disconnected.push(id);
+	}
+
+	fn controller_button_pressed(&mut self, _s: &mut Sketch, id: i32, button: ControllerButton) {
+		self.pressed.push((id, button));
+	}
+
+	fn controller_button_released(&mut self, _s: &mut Sketch, id: i32, button: ControllerButton) {
+		self.released.push((id, button));
+	}
+
+	fn controller_axis_moved(&mut self, _s: &mut Sketch, id: i32, axis: ControllerAxis, value: f32) {
+		self.axes.push((id, axis, value));
+	}
+}
+
+#[test]
+fn test_controller_events() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = ControllerLog::default();
+	s.push_event(Event::ControllerDeviceAdded { timestamp: 0, which: 0 });
+	s.push_event(Event::ControllerButtonDown { timestamp: 0, which: 0, button: ControllerButton::A });
+	s.push_event(Event::ControllerButtonDown { timestamp: 0, which: 0, button: ControllerButton::B });
+	s.push_event(Event::ControllerButtonUp { timestamp: 0, which: 0, button: ControllerButton::B });
+	s.push_event(Event::ControllerAxisMotion { timestamp: 0, which: 0, axis: ControllerAxis::LeftX, value: 32767 });
+	s.push_event(Event::ControllerAxisMotion { timestamp: 0, which: 0, axis: ControllerAxis::LeftY, value: -1000 });
+	s.push_event(Event::Quit { timestamp: 0 });
+	sdl2sketch::run(&mut s, &mut log);
+
+	assert_eq!(log.connected, vec![0]);
+	assert_eq!(log.pressed, vec![(0, ControllerButton::A), (0, ControllerButton::B)]);
+	assert_eq!(log.released, vec![(0, ControllerButton::B)]);
+	assert_eq!(log.axes, vec![(0, ControllerAxis::LeftX, 1.0), (0, ControllerAxis::LeftY, 0.0)]);
+	assert_eq!(s.controllers(), vec![0]);
+	assert!(s.controller_button_is_down(0, ControllerButton::A));
+	assert!(!s.controller_button_is_down(0, ControllerButton::B));
+	assert_eq!(s.controller_axis(0, ControllerAxis::LeftX), 1.0);
+	assert_eq!(s.controller_axis(0, ControllerAxis::LeftY), 0.0);
+
+	s.bind_action("jump", Binding::ControllerButton(ControllerButton::A));
+	s.bind_axis("move_x", AxisBinding::ControllerAxis(ControllerAxis::LeftX));
+	assert!(s.action_down("jump"));
+	assert_eq!(s.axis("move_x"), 1.0);
+}
+
+#[test]
+fn test_controller_dead_zone() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = ControllerLog::default();
+	s.controller_dead_zone(0.5);
+	s.push_event(Event::ControllerAxisMotion { timestamp: 0, which: 3, axis: ControllerAxis::RightX, value: -16383 });
+	s.push_event(Event::ControllerAxisMotion { timestamp: 0, which: 3, axis: ControllerAxis::RightY, value: -32768 });
+	s.push_event(Event::ControllerDeviceRemoved { timestamp: 0, which: 3 });
+	s.push_event(Event::Quit { timestamp: 0 });
+	sdl2sketch::run(&mut s, &mut log);
+
+	assert_eq!(log.axes[0].2, 0.0);
+	assert_eq!(log.axes[1].2, -1.0);
+	assert_eq!(log.disconnected, vec![3]);
+	assert!(s.controllers().is_empty());
+	assert_eq!(s.controller_axis(3, ControllerAxis::RightY), 0.0);
+}
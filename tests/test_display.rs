@@ -0,0 +1,52 @@
+extern crate sdl2;
+extern crate sdl2sketch;
+use sdl2sketch::testing;
+
+#[test]
+fn test_displays() {
+	let s = testing::headless_sketch(320, 240);
+	let displays = s.displays();
+	assert!(!displays.is_empty());
+	assert_eq!(displays[0].index, 0);
+	assert_eq!(displays[0].width, s.display_width());
+	assert_eq!(displays[0].height, s.display_height());
+	assert!(s.display_width() > 0 && s.display_height() > 0);
+	assert!(s.display_refresh_rate() >= 0);
+}
+
+#[test]
+fn test_fullscreen() {
+	let mut s = testing::headless_sketch(320, 240);
+	assert!(!s.is_fullscreen());
+	s.fullscreen(true);
+	assert!(s.is_fullscreen());
+	s.fullscreen(false);
+	assert!(!s.is_fullscreen());
+	s.fullscreen_on_display(0);
+	assert!(s.is_fullscreen());
+}
+
+#[test]
+fn test_resize_canvas_logical_size() {
+	let mut s = testing::headless_sketch(320, 240);
+	s.logical_size(64, 40);
+	assert_eq!((s.width(), s.height()), (64, 40));
+	s.resize_canvas(640, 400);
+	assert_eq!((s.width(), s.height()), (64, 40));
+	s.no_logical_size();
+	assert_eq!((s.width(), s.height()), (640, 400));
+}
+
+#[test]
+fn test_pixel_art() {
+	let mut s = testing::headless_sketch(640, 400);
+	sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "linear");
+	s.pixel_art(64, 40);
+	assert_eq!(sdl2::hint::get("SDL_RENDER_SCALE_QUALITY"), Some("nearest".to_string()));
+	assert_eq!((s.width(), s.height()), (64, 40));
+	s.resize_canvas(700, 500);
+	assert_eq!((s.width(), s.height()), (64, 40));
+	s.no_pixel_art();
+	assert_eq!((s.width(), s.height()), (700, 500));
+	assert_eq!(sdl2::hint::get("SDL_RENDER_SCALE_QUALITY"), Some("linear".to_string()));
+}
@@ -0,0 +1,514 @@
+extern crate sdl2;
+extern crate sdl2_sys;
+extern crate sdl2sketch;
+use sdl2sketch::*;
+use sdl2sketch::testing;
+use sdl2::mouse::MouseWheelDirection;
+
+use std::path::{Path, PathBuf};
+
+#[derive(Default)]
+struct DropLog {
+	files: Vec<PathBuf>,
+	image_size: Option<(u32, u32)>,
+}
+
+impl MainLoopMethods for DropLog {
+	fn file_dropped(&mut self, s: &mut Sketch, path: &Path) {
+		self.files.push(path.to_path_buf());
+		if let Ok(img) = s.try_load_image(path) {
+			let query = img.query();
+			self.image_size = Some((query.width, query.height));
+		}
+	}
+}
+
+#[test]
+fn test_file_dropped() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = DropLog::default();
+	s.push_event(Event::DropFile { timestamp: 0, filename: "Cargo.toml".to_string() });
+	s.push_event(Event::DropFile { timestamp: 0, filename: "examples/pixelcar_64x40.png".to_string() });
+	s.push_event(Event::Quit { timestamp: 0 });
+	sdl2sketch::run(&mut s, &mut log);
+
+	assert_eq!(log.files, vec![PathBuf::from("Cargo.toml"), PathBuf::from("examples/pixelcar_64x40.png")]);
+	assert_eq!(log.image_size, Some((64, 40)));
+}
+
+#[derive(Default)]
+struct WindowLog {
+	close_requests: u32,
+	calls: Vec<&'static str>,
+}
+
+impl MainLoopMethods for WindowLog {
+	fn close_requested(&mut self, _s: &mut Sketch) -> bool {
+		self.close_requests += 1;
+		self.close_requests > 1 // veto the first request
+	}
+
+	fn window_focused(&mut self, _s: &mut Sketch) { self.calls.push("focused"); }
+	fn window_unfocused(&mut self, _s: &mut Sketch) { self.calls.push("unfocused"); }
+	fn mouse_entered(&mut self, _s: &mut Sketch) { self.calls.push("entered"); }
+	fn mouse_left(&mut self, _s: &mut Sketch) { self.calls.push("left"); }
+	fn window_minimized(&mut self, _s: &mut Sketch) { self.calls.push("minimized"); }
+	fn window_restored(&mut self, _s: &mut Sketch) { self.calls.push("restored"); }
+}
+
+fn window_event(win_event: WindowEvent) -> Event {
+	Event::Window { timestamp: 0, window_id: 0, win_event }
+}
+
+#[test]
+fn test_window_events() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = WindowLog::default();
+	s.push_event(window_event(WindowEvent::FocusLost));
+	s.push_event(window_event(WindowEvent::Leave));
+	s.push_event(window_event(WindowEvent::Minimized));
+	s.push_event(Event::Quit { timestamp: 0 });
+	s.push_event(window_event(WindowEvent::Restored));
+	s.push_event(window_event(WindowEvent::Enter));
+	s.push_event(Event::Quit { timestamp: 0 });
+	sdl2sketch::run(&mut s, &mut log);
+
+	assert_eq!(log.close_requests, 2);
+	assert_eq!(log.calls, vec!["unfocused", "left", "minimized", "restored", "entered"]);
+	assert!(!s.is_focused());
+
+	s.push_event(window_event(WindowEvent::FocusGained));
+	s.push_event(Event::Quit { timestamp: 0 });
+	sdl2sketch::run(&mut s, &mut log);
+	assert!(s.is_focused());
+}
+
+#[derive(Default)]
+struct ResizeLog {
+	sizes: Vec<(i32, i32)>,
+	sketch_sizes: Vec<(i32, i32)>,
+}
+
+impl MainLoopMethods for ResizeLog {
+	fn window_resized(&mut self, s: &mut Sketch, w: i32, h: i32) {
+		self.sizes.push((w, h));
+		self.sketch_sizes.push((s.width(), s.height()));
+	}
+}
+
+#[test]
+fn test_window_resized() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = ResizeLog::default();
+	s.push_event(window_event(WindowEvent::SizeChanged(400, 300)));
+	s.run_frames(&mut log, 1);
+	assert_eq!(log.sizes, vec![(400, 300)]);
+	assert_eq!(log.sketch_sizes, vec![(400, 300)]); // already updated when window_resized() is called
+	assert_eq!((s.width(), s.height()), (400, 300));
+}
+
+#[test]
+fn test_resize_canvas() {
+	let mut s = testing::headless_sketch(320, 240);
+	s.resize_canvas(400, 300);
+	assert_eq!((s.width(), s.height()), (400, 300));
+	s.resize_canvas(200, 100);
+	assert_eq!((s.width(), s.height()), (200, 100));
+}
+
+#[derive(Default)]
+struct WheelLog {
+	wheels: Vec<(f32, f32)>,
+	deltas: Vec<(f32, f32)>,
+}
+
+impl MainLoopMethods for WheelLog {
+	fn update(&mut self, s: &mut Sketch) {
+		self.deltas.push(s.mouse_wheel_delta());
+	}
+
+	fn mouse_wheel(&mut self, _s: &mut Sketch, dx: f32, dy: f32) {
+		self.wheels.push((dx, dy));
+	}
+}
+
+fn mouse_wheel(x: i32, y: i32, direction: MouseWheelDirection) -> Event {
+	Event::MouseWheel { timestamp: 0, window_id: 0, which: 0, x, y, direction }
+}
+
+#[test]
+fn test_mouse_wheel() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = WheelLog::default();
+	s.push_event(mouse_wheel(0, 1, MouseWheelDirection::Normal));
+	s.push_event(mouse_wheel(2, 1, MouseWheelDirection::Normal));
+	s.push_event(mouse_wheel(0, 3, MouseWheelDirection::Flipped));
+	s.run_frames(&mut log, 2);
+
+	assert_eq!(log.wheels, vec![(0.0, 1.0), (2.0, 1.0), (0.0, -3.0)]);
+	assert_eq!(log.deltas, vec![(2.0, -1.0), (0.0, 0.0)]); // accumulated within a frame and reset afterwards
+	assert_eq!(s.mouse_wheel_delta(), (0.0, 0.0));
+}
+
+struct Empty;
+
+impl MainLoopMethods for Empty {}
+
+#[test]
+fn test_unfocused_framerate() {
+	let mut s = testing::headless_sketch(320, 240);
+	s.clock_mode(ClockMode::VIRTUAL); // the virtual clock advances by 1/fps per frame
+	s.set_framerate(50);
+	s.set_unfocused_framerate(10);
+	s.push_event(window_event(WindowEvent::FocusLost));
+	s.run_frames(&mut Empty, 2);
+	assert_eq!(s.delta_time(), 100);
+
+	s.set_framerate(25); // applied when the window gains the focus again
+	s.run_frames(&mut Empty, 1);
+	assert_eq!(s.delta_time(), 100);
+
+	s.push_event(window_event(WindowEvent::FocusGained));
+	s.run_frames(&mut Empty, 2);
+	assert_eq!(s.delta_time(), 40);
+
+	s.push_event(window_event(WindowEvent::FocusLost));
+	s.set_unfocused_framerate(0);
+	s.run_frames(&mut Empty, 2);
+	assert_eq!(s.delta_time(), 40);
+}
+
+#[derive(Default)]
+struct RedrawLog {
+	updates: u32,
+	draws: u32,
+	texts: u32,
+}
+
+impl MainLoopMethods for RedrawLog {
+	fn setup(&mut self, s: &mut Sketch) {
+		s.no_loop(true);
+	}
+
+	fn update(&mut self, _s: &mut Sketch) {
+		self.updates += 1;
+	}
+
+	fn draw(&mut self, s: &mut Sketch) {
+		self.draws += 1;
+		if self.draws == 1 {
+			s.push_event(key_down(Keycode::R));
+			s.push_event(key_down(Keycode::R));
+		} else {
+			s.push_event(Event::TextInput { timestamp: 0, window_id: 0, text: "r".to_string() });
+			s.push_event(Event::Quit { timestamp: 0 });
+		}
+	}
+
+	fn key_pressed(&mut self, s: &mut Sketch, _key: KeyEvent) {
+		s.redraw();
+	}
+
+	fn key_typed(&mut self, _s: &mut Sketch, _text: &str) {
+		self.texts += 1;
+	}
+}
+
+fn key_down(keycode: Keycode) -> Event {
+	Event::KeyDown { timestamp: 0, window_id: 0, keycode: Some(keycode), scancode: None, keymod: KeyMod::empty(), repeat: false }
+}
+
+#[test]
+fn test_redraw() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = RedrawLog::default();
+	sdl2sketch::run(&mut s, &mut log);
+
+	assert_eq!(log.updates, 2);
+	assert_eq!(log.draws, 2);
+	assert_eq!(log.texts, 1); // handled while stopped, without a frame
+}
+
+#[derive(Default)]
+struct InputLog {
+	pressed: Vec<(Keycode, bool)>,
+	typed: String,
+	drags: Vec<(i32, i32, i32, i32)>,
+	clicks: u32,
+	key_down_in_update: bool,
+}
+
+impl MainLoopMethods for InputLog {
+	fn update(&mut self, s: &mut Sketch) {
+		self.key_down_in_update = s.key_is_down(Keycode::Z);
+	}
+
+	fn key_pressed(&mut self, _s: &mut Sketch, key: KeyEvent) {
+		self.pressed.push((key.keycode, key.ctrl()));
+	}
+
+	fn key_typed(&mut self, _s: &mut Sketch, text: &str) {
+		self.typed.push_str(text);
+	}
+
+	fn mouse_dragged(&mut self, _s: &mut Sketch, drag: &MouseDrag, x: i32, y: i32, xrel: i32, yrel: i32) {
+		assert_eq!(drag.buttons, vec![MouseButton::Left]);
+		assert_eq!((drag.start_x, drag.start_y), (10, 20));
+		self.drags.push((x, y, xrel, yrel));
+	}
+
+	fn mouse_clicked(&mut self, _s: &mut Sketch, _button: MouseButton, _x: i32, _y: i32) {
+		self.clicks += 1;
+	}
+}
+
+#[test]
+fn test_push_input() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = InputLog::default();
+	s.push_key(Keycode::LCtrl, true);
+	s.push_key(Keycode::Z, true);
+	s.push_key(Keycode::LCtrl, false);
+	s.push_text("ab");
+	s.push_mouse_move(10, 20);
+	s.push_mouse_button(MouseButton::Left, true, 1);
+	s.push_mouse_move(15, 22);
+	s.push_mouse_move(30, 40);
+	s.run_frames(&mut log, 1);
+
+	assert_eq!(log.pressed, vec![(Keycode::LCtrl, true), (Keycode::Z, true)]);
+	assert!(log.key_down_in_update);
+	assert!(s.key_is_down(Keycode::Z));
+	assert!(!s.key_is_down(Keycode::LCtrl));
+	assert_eq!(log.typed, "ab");
+	assert_eq!(log.drags, vec![(15, 22, 5, 2), (30, 40, 15, 18)]);
+	assert_eq!(s.mouse_pos(), (30, 40));
+	assert!(s.mouse_is_pressed());
+
+	s.push_mouse_button(MouseButton::Left, false, 1);
+	s.push_key(Keycode::Z, false);
+	s.run_frames(&mut log, 1);
+	assert_eq!(log.clicks, 1);
+	assert!(!s.mouse_is_pressed());
+	assert!(!log.key_down_in_update);
+	assert_eq!(s.pmouse_pos(), (30, 40));
+}
+
+#[test]
+fn test_push_mouse_events() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = InputLog::default();
+	s.push_event(Event::MouseMotion { timestamp: 0, window_id: 0, which: 0, mousestate: sdl2::mouse::MouseState::from_sdl_state(0), x: 12, y: 34, xrel: 2, yrel: 4 });
+	s.push_event(Event::MouseButtonDown { timestamp: 0, window_id: 0, which: 0, mouse_btn: MouseButton::Right, clicks: 1, x: 13, y: 35 });
+	s.run_frames(&mut log, 1);
+	assert_eq!(s.mouse_pos(), (13, 35));
+	assert!(s.mouse_is_pressed());
+
+	s.push_event(Event::MouseButtonUp { timestamp: 0, window_id: 0, which: 0, mouse_btn: MouseButton::Right, clicks: 1, x: 14, y: 36 });
+	s.run_frames(&mut log, 1);
+	assert_eq!(s.mouse_pos(), (14, 36));
+	assert!(!s.mouse_is_pressed());
+}
+
+#[derive(Default)]
+struct OrderLog {
+	events: Vec<String>,
+}
+
+impl MainLoopMethods for OrderLog {
+	fn close_requested(&mut self, _s: &mut Sketch) -> bool {
+		self.events.push("quit".to_string());
+		true
+	}
+
+	fn key_typed(&mut self, _s: &mut Sketch, text: &str) {
+		self.events.push(text.to_string());
+	}
+}
+
+#[test]
+fn test_push_event_order() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = OrderLog::default();
+	s.push_text("a");
+	let mut quit: sdl2_sys::SDL_Event = unsafe { std::mem::zeroed() };
+	quit.type_ = sdl2_sys::SDL_EventType::SDL_QUIT as u32;
+	assert_eq!(unsafe { sdl2_sys::SDL_PushEvent(&mut quit) }, 1); // an event from SDL
+	s.push_text("this text is longer than one SDL text event");
+	sdl2sketch::run(&mut s, &mut log);
+
+	assert_eq!(log.events, vec!["a", "quit", "this text is longer than one SD", "L text event"]);
+}
+
+#[derive(Default)]
+struct ClickLog {
+	drag_starts: Vec<(i32, i32)>,
+	clicks: Vec<(i32, i32)>,
+	double_clicks: Vec<(i32, i32)>,
+	positions: Vec<((i32, i32), (i32, i32))>,
+}
+
+impl MainLoopMethods for ClickLog {
+	fn update(&mut self, s: &mut Sketch) {
+		self.positions.push((s.pmouse_pos(), s.mouse_pos()));
+	}
+
+	fn mouse_dragged(&mut self, _s: &mut Sketch, drag: &MouseDrag, _x: i32, _y: i32, _xrel: i32, _yrel: i32) {
+		self.drag_starts.push((drag.start_x, drag.start_y));
+	}
+
+	fn mouse_clicked(&mut self, _s: &mut Sketch, _button: MouseButton, x: i32, y: i32) {
+		self.clicks.push((x, y));
+	}
+
+	fn double_clicked(&mut self, _s: &mut Sketch, _button: MouseButton, x: i32, y: i32) {
+		self.double_clicks.push((x, y));
+	}
+}
+
+#[test]
+fn test_mouse_drag_and_clicks() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = ClickLog::default();
+	s.push_mouse_move(10, 20);
+	s.push_mouse_button(MouseButton::Left, true, 1);
+	s.push_mouse_move(15, 25);
+	s.push_mouse_button(MouseButton::Right, true, 1); // the drag started with the first button
+	s.push_mouse_move(30, 40);
+	s.push_mouse_button(MouseButton::Right, false, 1);
+	s.push_mouse_button(MouseButton::Left, false, 1);
+	s.run_frames(&mut log, 1);
+	assert_eq!(log.drag_starts, vec![(10, 20), (10, 20)]);
+	assert_eq!(log.clicks, vec![(30, 40), (30, 40)]);
+	assert!(log.double_clicks.is_empty());
+
+	s.push_mouse_move(50, 60);
+	s.push_mouse_button(MouseButton::Left, true, 1);
+	s.push_mouse_button(MouseButton::Left, false, 1);
+	s.push_mouse_button(MouseButton::Left, true, 2);
+	s.push_mouse_button(MouseButton::Left, false, 2);
+	s.run_frames(&mut log, 1);
+	assert_eq!(log.clicks.len(), 4); // the second click of a double click is a click, too
+	assert_eq!(log.double_clicks, vec![(50, 60)]);
+	assert_eq!(log.positions, vec![((0, 0), (30, 40)), ((30, 40), (50, 60))]);
+	assert_eq!(s.pmouse_pos(), (50, 60));
+}
+
+#[derive(Default)]
+struct KeyLog {
+	pressed: Vec<KeyEvent>,
+	released: Vec<KeyEvent>,
+	typed: Vec<String>,
+}
+
+impl MainLoopMethods for KeyLog {
+	fn key_pressed(&mut self, _s: &mut Sketch, key: KeyEvent) {
+		self.pressed.push(key);
+	}
+
+	fn key_released(&mut self, _s: &mut Sketch, key: KeyEvent) {
+		self.released.push(key);
+	}
+
+	fn key_typed(&mut self, _s: &mut Sketch, text: &str) {
+		self.typed.push(text.to_string());
+	}
+}
+
+fn key_event(down: bool, keycode: Option<Keycode>, scancode: Option<Scancode>, keymod: KeyMod) -> Event {
+	if down {
+		Event::KeyDown { timestamp: 0, window_id: 0, keycode, scancode, keymod, repeat: false }
+	} else {
+		Event::KeyUp { timestamp: 0, window_id: 0, keycode, scancode, keymod, repeat: false }
+	}
+}
+
+#[test]
+fn test_key_events() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = KeyLog::default();
+	let all_mods = sdl2::keyboard::LSHIFTMOD | sdl2::keyboard::RCTRLMOD | sdl2::keyboard::LALTMOD | sdl2::keyboard::RGUIMOD;
+	s.push_event(key_event(true, Some(Keycode::A), Some(Scancode::A), all_mods));
+	s.push_event(key_event(true, Some(Keycode::B), Some(Scancode::B), sdl2::keyboard::RSHIFTMOD));
+	s.push_event(key_event(true, None, Some(Scancode::International1), KeyMod::empty())); // key without keycode
+	s.push_event(Event::TextInput { timestamp: 0, window_id: 0, text: "A".to_string() });
+	s.run_frames(&mut log, 1);
+
+	assert_eq!(log.pressed.len(), 2);
+	let (a, b) = (log.pressed[0], log.pressed[1]);
+	assert_eq!((a.keycode, a.scancode), (Keycode::A, Some(Scancode::A)));
+	assert!(a.shift() && a.ctrl() && a.alt() && a.gui());
+	assert!(b.shift() && !b.ctrl() && !b.alt() && !b.gui());
+	assert_eq!(log.typed, vec!["A"]);
+	assert!(s.scancode_is_down(Scancode::A));
+	assert!(s.scancode_is_down(Scancode::International1));
+	assert!(!s.scancode_is_down(Scancode::C));
+
+	s.push_event(key_event(false, None, Some(Scancode::International1), KeyMod::empty()));
+	s.push_event(key_event(false, Some(Keycode::A), Some(Scancode::A), KeyMod::empty()));
+	s.run_frames(&mut log, 1);
+	assert_eq!(log.released.len(), 1);
+	assert!(!s.scancode_is_down(Scancode::International1));
+	assert!(!s.scancode_is_down(Scancode::A));
+	assert!(s.scancode_is_down(Scancode::B));
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct FrameInput {
+	z_went_down: bool,
+	z_went_up: bool,
+	z_held: u32,
+	x_went_down: bool,
+	x_went_up: bool,
+	x_is_down: bool,
+	left_went_down: bool,
+	left_went_up: bool,
+	mouse_delta: (i32, i32),
+}
+
+#[derive(Default)]
+struct FrameInputLog {
+	frames: Vec<FrameInput>,
+}
+
+impl MainLoopMethods for FrameInputLog {
+	fn update(&mut self, s: &mut Sketch) {
+		self.frames.push(FrameInput {
+			z_went_down: s.key_went_down(Keycode::Z),
+			z_went_up: s.key_went_up(Keycode::Z),
+			z_held: s.key_held_duration(Keycode::Z),
+			x_went_down: s.key_went_down(Keycode::X),
+			x_went_up: s.key_went_up(Keycode::X),
+			x_is_down: s.key_is_down(Keycode::X),
+			left_went_down: s.mouse_button_went_down(MouseButton::Left),
+			left_went_up: s.mouse_button_went_up(MouseButton::Left),
+			mouse_delta: s.mouse_delta(),
+		});
+	}
+}
+
+#[test]
+fn test_input_state() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = FrameInputLog::default();
+	s.clock_mode(ClockMode::VIRTUAL);
+	s.set_framerate(50); // 20 ms per frame
+	s.push_mouse_move(10, 10);
+	s.run_frames(&mut log, 1);
+
+	s.push_key(Keycode::Z, true);
+	s.push_mouse_button(MouseButton::Left, true, 1);
+	s.push_mouse_move(13, 14);
+	s.push_mouse_move(14, 13);
+	s.run_frames(&mut log, 2);
+
+	s.push_key(Keycode::Z, false);
+	s.push_key(Keycode::X, true);
+	s.push_key(Keycode::X, false);
+	s.push_mouse_button(MouseButton::Left, false, 1);
+	s.run_frames(&mut log, 1);
+
+	assert_eq!(log.frames[1], FrameInput { z_went_down: true, left_went_down: true, mouse_delta: (4, 3), ..FrameInput::default() });
+	assert_eq!(log.frames[2], FrameInput { z_held: 20, ..FrameInput::default() });
+	assert_eq!(log.frames[3], FrameInput { z_went_up: true, x_went_down: true, x_went_up: true, left_went_up: true, ..FrameInput::default() });
+}
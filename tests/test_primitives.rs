@@ -0,0 +1,136 @@
+extern crate sdl2sketch;
+use sdl2sketch::*;
+use sdl2sketch::testing::{self, Tolerance};
+
+use std::{env, fs};
+use std::path::{Path, PathBuf};
+
+/// sketch which draws a single frame with the provided function
+struct Drawing<F: FnMut(&mut Sketch)>(F);
+
+impl<F: FnMut(&mut Sketch)> MainLoopMethods for Drawing<F> {
+	fn draw(&mut self, s: &mut Sketch) {
+		s.background(Color::RGB(33, 33, 33));
+		(self.0)(s);
+	}
+}
+
+fn reference(name: &str) -> PathBuf {
+	Path::new("tests/snapshots").join(format!("{}.png", name))
+}
+
+/// draws a frame and compares it with the reference image tests/snapshots/<name>.png
+///
+/// The reference images are written by running the tests with the environment variable SDL2SKETCH_UPDATE_SNAPSHOTS set.
+/// The tests of shapes drawn with SDL2-gfx are ignored until their reference images are added, i.e. written with SDL2SKETCH_UPDATE_SNAPSHOTS=1 cargo test --test test_primitives -- --include-ignored.
+fn snapshot<F: FnMut(&mut Sketch)>(name: &str, f: F) {
+	let s = testing::run_headless(64, 64, &mut Drawing(f), 1);
+	testing::assert_snapshot(&s, &reference(name), Tolerance::new(8, 0.005));
+}
+
+#[test]
+fn test_compare_pixels() {
+	let expected = [10, 20, 30, 255, 100, 100, 100, 255];
+	let diff = testing::compare_pixels(&[12, 18, 30, 255, 100, 100, 100, 255], &expected, 2);
+	assert_eq!((diff.differing_pixels, diff.total_pixels), (0, 2));
+	let diff = testing::compare_pixels(&[13, 20, 30, 255, 100, 100, 100, 255], &expected, 2);
+	assert_eq!((diff.differing_pixels, diff.total_pixels), (1, 2));
+	assert_eq!(diff.ratio(), 0.5);
+	assert_eq!(&diff.image[0..4], &[255, 0, 0, 255]);
+}
+
+#[test]
+fn test_snapshot_mismatch() {
+	if env::var("SDL2SKETCH_UPDATE_SNAPSHOTS").is_ok() {
+		return; // the reference image would be overwritten instead of compared
+	}
+	let path = env::temp_dir().join("sdl2sketch_mismatch.png");
+	let _ = fs::remove_file(&path); // left over from a previous run
+	{
+		let s = testing::run_headless(16, 16, &mut Drawing( |s: &mut Sketch| s.background(Color::RGB(0, 0, 255)) ), 1);
+		assert!(testing::check_snapshot(&s, &path, Tolerance::exact()).is_err()); // missing reference image
+	}
+	fs::rename(path.with_extension("actual.png"), &path).unwrap(); // use the canvas as reference image
+	let s = testing::run_headless(16, 16, &mut Drawing( |s: &mut Sketch| { s.no_stroke(); s.rect(0, 0, 8, 16); } ), 1);
+	assert!(testing::check_snapshot(&s, &path, Tolerance::new(0, 0.6)).is_ok());
+	assert!(testing::check_snapshot(&s, &path, Tolerance::new(0, 0.4)).is_err());
+	assert!(path.with_extension("actual.png").exists());
+	assert!(path.with_extension("diff.png").exists());
+	for p in &[path.clone(), path.with_extension("actual.png"), path.with_extension("diff.png")] {
+		fs::remove_file(p).unwrap();
+	}
+}
+
+#[test]
+fn test_background() {
+	snapshot("background", |s| s.background(Color::RGB(200, 100, 50)));
+}
+
+#[test]
+#[ignore = "reference image of SDL2-gfx not generated yet, see snapshot()"]
+fn test_point_and_line() {
+	snapshot("point_and_line", |s| {
+		s.stroke(Color::RGB(255, 255, 0));
+		s.point(5, 5);
+		s.line(10, 10, 54, 30);
+		s.line(10, 50, 54, 50);
+	});
+}
+
+#[test]
+fn test_rect() {
+	snapshot("rect", |s| {
+		s.fill(Color::RGB(255, 0, 0));
+		s.stroke(Color::RGB(0, 255, 0));
+		s.rect(4, 4, 20, 12);
+		s.rect_mode(RectMode::CENTER);
+		s.no_stroke();
+		s.rect(40, 40, 16, 24);
+	});
+}
+
+#[test]
+#[ignore = "reference image of SDL2-gfx not generated yet, see snapshot()"]
+fn test_circle_and_ellipse() {
+	snapshot("circle_and_ellipse", |s| {
+		s.fill(Color::RGB(0, 128, 255));
+		s.stroke(Color::RGB(255, 255, 255));
+		s.circle(20, 20, 12);
+		s.no_smooth();
+		s.no_fill();
+		s.ellipse(40, 44, 20, 10);
+	});
+}
+
+#[test]
+#[ignore = "reference image of SDL2-gfx not generated yet, see snapshot()"]
+fn test_polygons() {
+	snapshot("polygons", |s| {
+		s.fill(Color::RGB(255, 0, 255));
+		s.triangle(4, 30, 20, 4, 30, 30);
+		s.no_stroke();
+		s.quad(34, 34, 60, 36, 58, 60, 36, 58);
+		s.fill(Color::RGB(0, 255, 255));
+		s.polygon(&[4, 14, 24, 14], &[48, 38, 48, 58]);
+	});
+}
+
+#[test]
+#[ignore = "reference image of SDL2-gfx not generated yet, see snapshot()"]
+fn test_arc_and_pie() {
+	snapshot("arc_and_pie", |s| {
+		s.stroke(Color::RGB(255, 128, 0));
+		s.arc(16, 16, 12, 0.0, 3.0);
+		s.fill(Color::RGB(128, 255, 0));
+		s.angle_mode(AngleMode::DEGREES);
+		s.pie(44, 44, 16, 45.0, 270.0);
+	});
+}
+
+#[test]
+fn test_image() {
+	snapshot("image", |s| {
+		let img = s.load_image(Path::new("examples/pixelcar_64x40.png"));
+		s.image(&img, 0, 12, 64, 40);
+	});
+}
@@ -0,0 +1,87 @@
+extern crate sdl2sketch;
+use sdl2sketch::*;
+use sdl2sketch::testing;
+
+/// the results of all random functions, see draw_numbers()
+#[derive(Debug, PartialEq)]
+struct Numbers {
+	floats: Vec<f32>,
+	ints: Vec<i32>,
+	gaussians: Vec<f32>,
+	shuffled: Vec<u8>,
+	choices: Vec<char>,
+	weighted: Vec<u32>,
+}
+
+fn draw_numbers(s: &mut Sketch) -> Numbers {
+	let floats = (0..20).map( |_| s.random(10.0) ).collect();
+	let ints = (0..20).map( |_| s.random_range(-3, 4) ).collect();
+	let gaussians = (0..20).map( |_| s.random_gaussian(5.0, 2.0) ).collect();
+	let mut shuffled: Vec<u8> = (0..20).collect();
+	s.shuffle(&mut shuffled);
+	let choices = (0..20).map( |_| *s.random_choice(&['a', 'b', 'c']).unwrap() ).collect();
+	let weighted = (0..20).map( |_| *s.random_weighted_choice(&[1, 2, 3], &[0.2, 0.3, 0.5]).unwrap() ).collect();
+	Numbers { floats, ints, gaussians, shuffled, choices, weighted }
+}
+
+#[test]
+fn test_random_seed() {
+	let mut s = testing::headless_sketch(320, 240);
+	s.random_seed(1234);
+	let first = draw_numbers(&mut s);
+	let second = draw_numbers(&mut s);
+	assert_ne!(first, second);
+
+	s.random_seed(1234);
+	assert_eq!(draw_numbers(&mut s), first);
+	s.random_seed(4321);
+	assert_ne!(draw_numbers(&mut s), first);
+}
+
+#[test]
+fn test_random_ranges() {
+	let mut s = testing::headless_sketch(320, 240);
+	for _ in 0..1000 {
+		let x = s.random(10.0);
+		assert!((0.0..10.0).contains(&x), "{}", x);
+		let x = s.random_range(-2.5, 2.5);
+		assert!((-2.5..2.5).contains(&x), "{}", x);
+		let n = s.random_range(5, 8);
+		assert!((5..8).contains(&n), "{}", n);
+	}
+
+	let values: Vec<f32> = (0..10_000).map( |_| s.random_gaussian(5.0, 2.0) ).collect();
+	let mean = values.iter().sum::<f32>() / values.len() as f32;
+	let sd = (values.iter().map( |x| (x - mean) * (x - mean) ).sum::<f32>() / values.len() as f32).sqrt();
+	assert!((mean - 5.0).abs() < 0.1, "{}", mean);
+	assert!((sd - 2.0).abs() < 0.1, "{}", sd);
+}
+
+#[test]
+fn test_random_choice_and_shuffle() {
+	let mut s = testing::headless_sketch(320, 240);
+	let empty: [u8; 0] = [];
+	assert_eq!(s.random_choice(&empty), None);
+	assert_eq!(s.random_weighted_choice(&[1, 2], &[0.0, -1.0]), None);
+	assert_eq!(s.random_weighted_choice(&[1, 2, 3], &[0.0, 1.0, 0.0]), Some(&2));
+
+	let mut counts = [0; 3];
+	for _ in 0..10_000 {
+		counts[*s.random_weighted_choice(&[0, 1, 2], &[1.0, 0.0, 3.0]).unwrap()] += 1;
+	}
+	assert_eq!(counts[1], 0);
+	assert!(counts[0] > 2200 && counts[0] < 2800, "{:?}", counts);
+
+	let mut items: Vec<u32> = (0..100).collect();
+	s.shuffle(&mut items);
+	assert_ne!(items, (0..100).collect::<Vec<u32>>());
+	items.sort();
+	assert_eq!(items, (0..100).collect::<Vec<u32>>());
+}
+
+#[test]
+#[should_panic(expected = "one weight per item")]
+fn test_random_weighted_choice_length_mismatch() {
+	let mut s = testing::headless_sketch(320, 240);
+	s.random_weighted_choice(&[1, 2, 3], &[0.0, 1.0]);
+}
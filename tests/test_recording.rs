@@ -0,0 +1,95 @@
+extern crate sdl2sketch;
+use sdl2sketch::*;
+use sdl2sketch::testing;
+
+use std::{env, fs};
+
+#[derive(Default)]
+struct Session {
+	live: bool,
+	random_value: f32,
+	keys: Vec<(u64, Keycode)>,
+	texts: Vec<(u64, String)>,
+	millis: Vec<u32>,
+	mouse: Vec<((i32, i32), bool)>,
+}
+
+impl MainLoopMethods for Session {
+	fn setup(&mut self, s: &mut Sketch) {
+		self.random_value = s.random(1.0);
+	}
+
+	fn update(&mut self, s: &mut Sketch) {
+		self.millis.push(s.millis());
+		self.mouse.push((s.mouse_pos(), s.mouse_is_pressed()));
+	}
+
+	fn draw(&mut self, s: &mut Sketch) {
+		if !self.live {
+			return;
+		}
+		match s.frame_count() {
+			1 => { s.push_key(Keycode::B, true); s.push_text("b \"c\"\t"); s.push_mouse_move(7, 8); s.push_mouse_button(MouseButton::Left, true, 1); },
+			3 => { s.push_event(Event::Quit { timestamp: 0 }); },
+			_ => {}
+		}
+	}
+
+	fn key_pressed(&mut self, s: &mut Sketch, key: KeyEvent) {
+		self.keys.push((s.frame_count(), key.keycode));
+	}
+
+	fn key_typed(&mut self, s: &mut Sketch, text: &str) {
+		self.texts.push((s.frame_count(), text.to_string()));
+	}
+}
+
+#[test]
+fn test_record_and_replay() {
+	let path = env::temp_dir().join("sdl2sketch_test_recording.jsonl");
+
+	let mut recorded = Session { live: true, ..Session::default() };
+	{
+		let mut s = testing::headless_sketch(320, 240);
+		s.record_input(&path).unwrap();
+		s.push_key(Keycode::A, true);
+		sdl2sketch::run(&mut s, &mut recorded);
+		s.stop_recording();
+	}
+	assert_eq!(recorded.keys, vec![(1, Keycode::A), (2, Keycode::B)]);
+
+	let mut replayed = Session::default();
+	let mut s = testing::headless_sketch(320, 240);
+	s.replay_input(&path).unwrap();
+	assert!(s.is_replaying());
+	sdl2sketch::run(&mut s, &mut replayed);
+	fs::remove_file(&path).unwrap();
+
+	assert!(!s.is_replaying());
+	assert_eq!(replayed.random_value, recorded.random_value);
+	assert_eq!(replayed.keys, recorded.keys);
+	assert_eq!(replayed.texts, vec![(2, "b \"c\"\t".to_string())]); // quotes and control characters are escaped in the recording
+	assert_eq!(replayed.mouse, recorded.mouse); // polled mouse state does not depend on the real mouse
+	assert_eq!(replayed.mouse[1], ((7, 8), true)); // pushed in frame 1, handled in frame 2
+	assert_eq!(replayed.millis, vec![5, 10, 15, 20]); // frame-based clock with 200 fps, the Quit event is handled in frame 4
+}
+
+#[test]
+fn test_replay_errors() {
+	let path = env::temp_dir().join("sdl2sketch_test_bad_recording.jsonl");
+	let mut s = testing::headless_sketch(320, 240);
+	assert!(s.replay_input(&path.with_extension("missing")).is_err());
+
+	fs::write(&path, "{\"sdl2sketch_recording\":1,\"seed\":42}\n{\"frame\":1,\"time\":5,\"type\":\"key_down\",\"keycode\":\"NoSuchKey\",\"keymod\":0,\"repeat\":false}\n").unwrap();
+	let err = s.replay_input(&path).unwrap_err();
+	assert!(err.contains("line 2"), "{}", err);
+
+	fs::write(&path, "{\"sdl2sketch_recording\":1,\"seed\":42}\n{\"frame\":1,\"time\":5,\"type\":\"text_input\",\"text\":\"a}\n").unwrap();
+	let err = s.replay_input(&path).unwrap_err();
+	assert!(err.contains("line 2"), "{}", err);
+
+	fs::write(&path, "{\"sdl2sketch_recording\":99,\"seed\":42}\n").unwrap();
+	assert!(s.replay_input(&path).is_err());
+	fs::remove_file(&path).unwrap();
+	assert!(!s.is_replaying());
+}
@@ -0,0 +1,92 @@
+extern crate sdl2sketch;
+use sdl2sketch::*;
+use sdl2sketch::testing;
+
+use std::time::{Duration, Instant};
+
+#[derive(Default)]
+struct Counter {
+	setups: u32,
+	updates: u32,
+	draws: u32,
+	quit_at: Option<u32>,
+}
+
+impl MainLoopMethods for Counter {
+	fn setup(&mut self, _s: &mut Sketch) {
+		self.setups += 1;
+	}
+
+	fn update(&mut self, s: &mut Sketch) {
+		self.updates += 1;
+		if Some(self.updates) == self.quit_at {
+			s.quit();
+		}
+	}
+
+	fn draw(&mut self, _s: &mut Sketch) {
+		self.draws += 1;
+	}
+}
+
+#[test]
+fn test_step() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut counter = Counter::default();
+	assert!(s.step(&mut counter));
+	assert!(s.step(&mut counter));
+	assert_eq!((counter.setups, counter.updates, counter.draws), (1, 2, 2));
+	assert_eq!(s.frame_count(), 2);
+
+	s.run_frames(&mut counter, 3);
+	assert_eq!((counter.setups, counter.updates, counter.draws), (1, 5, 5));
+
+	s.run_until(&mut counter, |s, _| s.frame_count() == 8);
+	assert_eq!(counter.draws, 8);
+	s.run_until(&mut counter, |_, c| c.draws == 8);
+	assert_eq!(counter.draws, 8);
+}
+
+#[test]
+fn test_step_quit() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut counter = Counter { quit_at: Some(2), ..Counter::default() };
+	s.run_frames(&mut counter, 10);
+	assert_eq!(counter.draws, 2);
+	assert!(!s.step(&mut counter));
+	assert_eq!((counter.updates, counter.draws), (2, 2)); // no frame after quit()
+}
+
+#[test]
+fn test_run_after_quit() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut counter = Counter { quit_at: Some(2), ..Counter::default() };
+	run(&mut s, &mut counter);
+	counter.quit_at = Some(4);
+	run(&mut s, &mut counter);
+	assert_eq!((counter.setups, counter.draws), (1, 4));
+}
+
+#[test]
+fn test_run_after_step() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut counter = Counter { quit_at: Some(3), ..Counter::default() };
+	s.step(&mut counter);
+	run(&mut s, &mut counter);
+	assert_eq!((counter.setups, counter.draws), (1, 3));
+}
+
+#[test]
+fn test_step_no_loop() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut counter = Counter::default();
+	s.no_loop(true);
+	let start = Instant::now();
+	s.run_frames(&mut counter, 3);
+	assert_eq!(counter.draws, 1); // the first frame is always drawn
+	assert!(start.elapsed() >= Duration::from_millis(400)); // the two idle steps wait for events instead of spinning
+
+	s.redraw();
+	s.run_frames(&mut counter, 3);
+	assert_eq!(counter.draws, 2);
+}
@@ -0,0 +1,134 @@
+extern crate sdl2sketch;
+use sdl2sketch::testing;
+use sdl2sketch::synth::*;
+
+use std::env;
+use std::fs;
+
+const RATE: usize = DEFAULT_SAMPLE_RATE as usize;
+
+fn render(synth: &mut Synth, seconds: f32) -> Vec<f32> {
+	let mut samples = vec![0.0; (seconds * RATE as f32) as usize];
+	synth.render(&mut samples);
+	samples
+}
+
+fn rising_zero_crossings(samples: &[f32]) -> usize {
+	samples.windows(2).filter( |w| w[0] < 0.0 && w[1] >= 0.0 ).count()
+}
+
+fn peak(samples: &[f32]) -> f32 {
+	samples.iter().fold(0.0f32, |max, x| max.max(x.abs()) )
+}
+
+#[test]
+fn test_oscillators() {
+	for &waveform in &[Waveform::SINE, Waveform::SQUARE, Waveform::SAW, Waveform::TRIANGLE] {
+		let mut synth = Synth::new();
+		let voice = synth.add_voice(waveform, 440.0);
+		voice.note_on();
+		let samples = render(&mut synth, 1.0);
+		let crossings = rising_zero_crossings(&samples);
+		assert!((439..=441).contains(&crossings), "{:?}: {} periods", waveform, crossings);
+		assert!((peak(&samples) - 1.0).abs() < 0.01, "{:?}", waveform);
+	}
+
+	let mut synth = Synth::new();
+	synth.add_voice(Waveform::NOISE, 0.0).note_on();
+	let samples = render(&mut synth, 0.1);
+	assert!(peak(&samples) <= 1.0);
+	assert!(rising_zero_crossings(&samples) > 100);
+	let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+	assert!(mean.abs() < 0.05);
+}
+
+#[test]
+fn test_silent_until_note_on() {
+	let mut synth = Synth::new();
+	let voice = synth.add_voice(Waveform::SINE, 440.0);
+	assert_eq!(peak(&render(&mut synth, 0.1)), 0.0);
+	voice.note_on();
+	assert!(peak(&render(&mut synth, 0.1)) > 0.9);
+	voice.note_off();
+	assert_eq!(peak(&render(&mut synth, 0.1)), 0.0);
+}
+
+#[test]
+fn test_envelope() {
+	let mut synth = Synth::new();
+	let voice = synth.add_voice(Waveform::SQUARE, 100.0);
+	voice.set_envelope(Envelope::new(0.1, 0.1, 0.5, 0.2));
+	assert_eq!(voice.envelope(), Envelope::new(0.1, 0.1, 0.5, 0.2));
+	voice.note_on();
+
+	let attack = render(&mut synth, 0.1);
+	assert!(peak(&attack[..RATE / 100]) < 0.15);
+	assert!(peak(&attack[RATE / 20..]) > 0.5);
+	let decay = render(&mut synth, 0.1);
+	assert!(peak(&decay[RATE / 20..]) < 0.8);
+	let sustain = render(&mut synth, 0.2);
+	assert!((peak(&sustain) - 0.5).abs() < 0.01);
+
+	voice.note_off();
+	let release = render(&mut synth, 0.1);
+	assert!((peak(&release[..RATE / 100]) - 0.5).abs() < 0.05);
+	assert!(peak(&release[RATE / 20..]) < 0.4);
+	let end = render(&mut synth, 0.2);
+	assert!(peak(&end[RATE / 10..]) < 0.001);
+}
+
+#[test]
+fn test_params() {
+	let mut synth = Synth::new();
+	let voice = synth.add_voice(Waveform::SINE, 440.0);
+	let gain = synth.gain();
+	voice.note_on();
+	voice.clone().set_frequency(220.0);
+	voice.set_amplitude(0.5);
+	gain.set(0.5);
+	assert_eq!(voice.frequency(), 220.0);
+	assert_eq!(gain.get(), 0.5);
+
+	let samples = render(&mut synth, 1.0);
+	assert!((219..=221).contains(&rising_zero_crossings(&samples)));
+	assert!((peak(&samples) - 0.25).abs() < 0.01);
+
+	voice.set_waveform(Waveform::SQUARE);
+	assert_eq!(voice.waveform(), Waveform::SQUARE);
+	let second = synth.add_voice(Waveform::SQUARE, 220.0);
+	second.note_on();
+	let samples = render(&mut synth, 0.1);
+	assert!((peak(&samples) - 0.75).abs() < 0.01);
+}
+
+#[test]
+fn test_render_to_wav() {
+	let mut synth = Synth::new();
+	synth.set_sample_rate(8000);
+	synth.add_voice(Waveform::TRIANGLE, 440.0).note_on();
+	let path = env::temp_dir().join("sdl2sketch_test_synth.wav");
+	synth.render_to_wav(&path, 0.5).unwrap();
+
+	let bytes = fs::read(&path).unwrap();
+	assert_eq!(bytes.len(), 44 + 4000 * 2);
+	assert_eq!(&bytes[0..4], b"RIFF");
+	assert_eq!(&bytes[8..16], b"WAVEfmt ");
+	assert_eq!(u32::from_le_bytes([bytes[24], bytes[25], bytes[26], bytes[27]]), 8000);
+	assert_eq!(&bytes[36..40], b"data");
+	let max = bytes[44..].chunks(2).map( |b| i16::from_le_bytes([b[0], b[1]]) ).max().unwrap();
+	assert!(max > 32000);
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_play_synth() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut synth = Synth::new();
+	let voice = synth.add_voice(Waveform::SAW, 110.0);
+	s.play_synth(synth).unwrap();
+	assert!(s.synth_is_playing());
+	voice.note_on();
+	voice.set_frequency(220.0);
+	s.stop_synth();
+	assert!(!s.synth_is_playing());
+}
@@ -0,0 +1,155 @@
+extern crate sdl2sketch;
+use sdl2sketch::*;
+use sdl2sketch::testing;
+
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Default)]
+struct FrameLog {
+	frames: Vec<u64>,
+	setup_frame: Option<u64>,
+}
+
+impl MainLoopMethods for FrameLog {
+	fn setup(&mut self, s: &mut Sketch) {
+		self.setup_frame = Some(s.frame_count());
+	}
+
+	fn update(&mut self, s: &mut Sketch) {
+		self.frames.push(s.frame_count());
+		if s.frame_count() == 3 {
+			s.quit();
+		}
+	}
+}
+
+#[test]
+fn test_frame_count() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = FrameLog::default();
+	s.set_framerate(200);
+	sdl2sketch::run(&mut s, &mut log);
+
+	assert_eq!(log.setup_frame, Some(0));
+	assert_eq!(log.frames, vec![1, 2, 3]);
+	assert!(s.millis() >= s.delta_time());
+}
+
+#[test]
+fn test_wall_clock() {
+	let s = testing::headless_sketch(320, 240);
+	assert!((0..60).contains(&s.second()));
+	assert!((0..60).contains(&s.minute()));
+	assert!((0..24).contains(&s.hour()));
+	assert!((1..32).contains(&s.day()));
+	assert!((1..13).contains(&s.month()));
+	assert!(s.year() >= 2018);
+}
+
+/// minutes since the Unix epoch of a date and time (days from civil, see http://howardhinnant.github.io/date_algorithms.html)
+fn epoch_minutes(year: i32, month: i32, day: i32, hour: i32, minute: i32) -> i64 {
+	let y = (if month <= 2 { year - 1 } else { year }) as i64;
+	let era = y.div_euclid(400);
+	let yoe = y - era * 400;
+	let mp = ((month + 9) % 12) as i64;
+	let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+	let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+	let days = era * 146097 + doe - 719468;
+	(days * 24 + hour as i64) * 60 + minute as i64
+}
+
+#[test]
+fn test_wall_clock_offset() {
+	// checks the conversion of localtime_r() and localtime_s() on Windows against the system time
+	let s = testing::headless_sketch(320, 240);
+	let utc = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64 / 60;
+	let local = epoch_minutes(s.year(), s.month(), s.day(), s.hour(), s.minute());
+	let offset = local - utc;
+	assert!((-12 * 60 ..= 14 * 60 + 1).contains(&offset), "offset {} min", offset);
+	assert!([0, 1, 14].contains(&offset.rem_euclid(15)), "offset {} min", offset); // time zones are in steps of 15 min, allowing a minute passing
+}
+
+#[derive(Default)]
+struct FixedUpdateLog {
+	updates: u32,
+	updates_per_frame: Vec<u32>,
+	alphas: Vec<f32>,
+}
+
+impl MainLoopMethods for FixedUpdateLog {
+	fn setup(&mut self, s: &mut Sketch) {
+		s.set_update_rate(10);
+		s.set_max_update_steps(2);
+	}
+
+	fn update(&mut self, _s: &mut Sketch) {
+		self.updates += 1;
+	}
+
+	fn draw(&mut self, s: &mut Sketch) {
+		self.updates_per_frame.push(self.updates);
+		self.alphas.push(s.update_alpha());
+		self.updates = 0;
+		if s.frame_count() == 1 {
+			thread::sleep(Duration::from_millis(250)); // more than two update steps
+		} else {
+			s.quit();
+		}
+	}
+}
+
+#[test]
+fn test_fixed_update_rate() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = FixedUpdateLog::default();
+	s.set_framerate(200);
+	sdl2sketch::run(&mut s, &mut log);
+
+	assert_eq!(log.updates_per_frame, vec![1, 2]);
+	assert!(log.alphas.iter().all( |a| *a >= 0.0 && *a < 1.0 ));
+}
+
+#[derive(Default)]
+struct TimeLog {
+	millis: Vec<u32>,
+	deltas: Vec<u32>,
+}
+
+impl MainLoopMethods for TimeLog {
+	fn update(&mut self, s: &mut Sketch) {
+		self.millis.push(s.millis());
+		self.deltas.push(s.delta_time());
+	}
+}
+
+#[test]
+fn test_virtual_clock() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = TimeLog::default();
+	s.clock_mode(ClockMode::VIRTUAL);
+	s.set_framerate(200);
+	s.run_frames(&mut log, 3);
+	assert_eq!(log.millis, vec![5, 10, 15]);
+	assert_eq!(log.deltas, vec![5, 5, 5]);
+
+	s.run_frames(&mut log, 197); // 1000 ms of virtual time in total
+	assert!((s.get_framerate() - 200.0).abs() < 0.01);
+
+	s.set_framerate(50);
+	s.run_frames(&mut log, 1);
+	assert_eq!(s.delta_time(), 20);
+	assert_eq!(s.millis(), 1020);
+}
+
+#[test]
+fn test_virtual_clock_fractional_frames() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = TimeLog::default();
+	s.clock_mode(ClockMode::VIRTUAL);
+	s.set_framerate(30);
+	s.run_frames(&mut log, 30);
+	assert_eq!(&log.millis[..4], &[33, 67, 100, 133]);
+	assert!(log.deltas.iter().all( |d| *d == 33 ), "{:?}", log.deltas);
+	assert_eq!(s.millis(), 1000); // no drift
+}
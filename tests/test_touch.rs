@@ -0,0 +1,116 @@
+extern crate sdl2sketch;
+use sdl2sketch::*;
+use sdl2sketch::testing;
+
+fn finger_down(finger_id: i64, x: f32, y: f32) -> Event {
+	Event::FingerDown { timestamp: 0, touch_id: 1, finger_id, x, y, dx: 0.0, dy: 0.0, pressure: 1.0 }
+}
+
+fn finger_motion(finger_id: i64, x: f32, y: f32, dx: f32, dy: f32) -> Event {
+	Event::FingerMotion { timestamp: 0, touch_id: 1, finger_id, x, y, dx, dy, pressure: 1.0 }
+}
+
+fn finger_up(finger_id: i64, x: f32, y: f32) -> Event {
+	Event::FingerUp { timestamp: 0, touch_id: 1, finger_id, x, y, dx: 0.0, dy: 0.0, pressure: 0.0 }
+}
+
+#[derive(Default)]
+struct TouchLog {
+	started: Vec<Touch>,
+	moved: Vec<Touch>,
+	ended: Vec<Touch>,
+	pinched: Vec<(f32, i32, i32)>,
+	rotated: Vec<(f32, i32, i32)>,
+	mouse_presses: u32,
+	touches_in_update: usize,
+}
+
+impl MainLoopMethods for TouchLog {
+	fn update(&mut self, s: &mut Sketch) {
+		self.touches_in_update = s.touches().len();
+	}
+
+	fn touch_started(&mut self, _s: &mut Sketch, touch: &Touch) {
+		self.started.push(*touch);
+	}
+
+	fn touch_moved(&mut self, _s: &mut Sketch, touch: &Touch) {
+		self.moved.push(*touch);
+	}
+
+	fn touch_ended(&mut self, _s: &mut Sketch, touch: &Touch) {
+		self.ended.push(*touch);
+	}
+
+	fn touch_pinched(&mut self, _s: &mut Sketch, d_dist: f32, x: i32, y: i32) {
+		self.pinched.push((d_dist, x, y));
+	}
+
+	fn touch_rotated(&mut self, _s: &mut Sketch, d_theta: f32, x: i32, y: i32) {
+		self.rotated.push((d_theta, x, y));
+	}
+
+	fn mouse_pressed(&mut self, _s: &mut Sketch, _button: MouseButton, _x: i32, _y: i32) {
+		self.mouse_presses += 1;
+	}
+}
+
+#[test]
+fn test_touch_events() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = TouchLog::default();
+	s.push_event(finger_down(7, 0.5, 0.5));
+	s.push_event(finger_down(8, 0.25, 0.75));
+	s.push_event(finger_motion(7, 0.75, 0.5, 0.25, 0.0));
+	s.push_event(finger_up(8, 0.25, 0.75));
+	s.push_event(Event::MultiGesture { timestamp: 0, touch_id: 1, d_theta: 0.1, d_dist: -0.05, x: 0.5, y: 0.5, num_fingers: 2 });
+	s.push_event(Event::Quit { timestamp: 0 });
+	sdl2sketch::run(&mut s, &mut log);
+
+	assert_eq!(log.started.len(), 2);
+	assert_eq!((log.started[0].id, log.started[0].x, log.started[0].y), (7, 160, 120));
+	assert_eq!((log.started[1].id, log.started[1].x, log.started[1].y), (8, 80, 180));
+	assert_eq!((log.moved[0].x, log.moved[0].y, log.moved[0].dx), (240, 120, 80.0));
+	assert_eq!(log.ended[0].id, 8);
+	assert_eq!(log.pinched, vec![(-0.05, 160, 120)]);
+	assert_eq!(log.rotated, vec![(0.1, 160, 120)]);
+	assert_eq!(log.touches_in_update, 1);
+	assert_eq!(s.touches()[0].id, 7);
+}
+
+#[test]
+fn test_touch_devices() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = TouchLog::default();
+	s.push_event(finger_down(7, 0.5, 0.5));
+	s.push_event(Event::FingerDown { timestamp: 0, touch_id: 2, finger_id: 7, x: 0.25, y: 0.25, dx: 0.0, dy: 0.0, pressure: 1.0 });
+	s.run_frames(&mut log, 1);
+	let devices: Vec<i64> = s.touches().iter().map( |t| t.touch_id ).collect();
+	assert_eq!(devices, vec![1, 2]);
+
+	s.push_event(finger_up(7, 0.5, 0.5));
+	s.run_frames(&mut log, 1);
+	assert_eq!(s.touches().len(), 1);
+	assert_eq!((s.touches()[0].touch_id, s.touches()[0].id, s.touches()[0].x), (2, 7, 80));
+	assert_eq!(log.ended[0].touch_id, 1);
+}
+
+#[test]
+fn test_touch_mouse_events() {
+	let mut s = testing::headless_sketch(320, 240);
+	let mut log = TouchLog::default();
+	let touch_mouse_press = Event::MouseButtonDown { timestamp: 0, window_id: 0, which: u32::MAX, mouse_btn: MouseButton::Left, clicks: 1, x: 10, y: 10 };
+	let real_mouse_press = Event::MouseButtonDown { timestamp: 0, window_id: 0, which: 0, mouse_btn: MouseButton::Left, clicks: 1, x: 10, y: 10 };
+	s.touch_mouse_events(false);
+	s.push_event(touch_mouse_press.clone());
+	s.push_event(real_mouse_press);
+	s.push_event(Event::Quit { timestamp: 0 });
+	sdl2sketch::run(&mut s, &mut log);
+	assert_eq!(log.mouse_presses, 1);
+
+	s.touch_mouse_events(true);
+	s.push_event(touch_mouse_press);
+	s.push_event(Event::Quit { timestamp: 0 });
+	sdl2sketch::run(&mut s, &mut log);
+	assert_eq!(log.mouse_presses, 2);
+}
@@ -1,6 +1,9 @@
+extern crate rand;
 extern crate sdl2sketch;
 use sdl2sketch::utils::*;
 
+use rand::{SeedableRng, XorShiftRng};
+
 use std::f32::consts::PI;
 
 #[test]
@@ -90,3 +93,97 @@ fn test_rgb_to_hsv() {
  	assert_eq!(rgb_to_hsv(  0,   0, 128), (240, 1.0, 0.50)); // Navy
 }
 
+
+fn sample_points() -> Vec<(f32, f32, f32)> {
+	(0..2000).map( |i| { let i = i as f32; (i * 0.173 - 50.0, i * 0.031, (i * 0.7).sin() * 20.0) } ).collect()
+}
+
+#[test]
+fn test_noise_determinism() {
+	let a = Noise::new(42);
+	let b = Noise::new(42);
+	let c = Noise::new(43);
+	let points = sample_points();
+	assert!(points.iter().all( |&(x, y, z)| a.noise3(x, y, z) == b.noise3(x, y, z) ));
+	assert!(points.iter().any( |&(x, y, z)| a.noise3(x, y, z) != c.noise3(x, y, z) ));
+
+	let mut d = Noise::new(1);
+	d.noise_seed(42);
+	assert_eq!(d.noise2(1.5, 2.5), a.noise2(1.5, 2.5));
+	assert_eq!(a.noise(3.7), a.noise3(3.7, 0.0, 0.0));
+	assert_eq!(a.noise2(-3.7, 1.2), a.noise2(3.7, 1.2)); // like p5.js
+}
+
+#[test]
+fn test_noise_range() {
+	let mut noise = Noise::new(7);
+	let values: Vec<f32> = sample_points().iter().map( |&(x, y, z)| noise.noise3(x, y, z) ).collect();
+	assert!(values.iter().all( |v| *v >= 0.0 && *v < 1.0 ));
+	let mean = values.iter().sum::<f32>() / values.len() as f32;
+	assert!((mean - 0.47).abs() < 0.1, "{}", mean);
+
+	// smooth
+	assert!((noise.noise2(10.0, 5.0) - noise.noise2(10.001, 5.0)).abs() < 0.01);
+
+	noise.noise_detail(1, 0.0);
+	assert!(sample_points().iter().all( |&(x, y, z)| noise.noise3(x, y, z) < 0.5 ));
+	noise.noise_detail(8, 0.25);
+	assert!(sample_points().iter().all( |&(x, y, z)| noise.noise3(x, y, z) < 2.0 / 3.0 ));
+}
+
+#[test]
+fn test_simplex_noise() {
+	let a = SimplexNoise::new(42);
+	let b = SimplexNoise::new(42);
+	let c = SimplexNoise::new(43);
+	let points = sample_points();
+	let values: Vec<f32> = points.iter().map( |&(x, y, z)| a.noise4(x, y, z, x - y) ).collect();
+	assert!(points.iter().zip(values.iter()).all( |(&(x, y, z), v)| b.noise4(x, y, z, x - y) == *v ));
+	assert!(points.iter().any( |&(x, y, z)| c.noise4(x, y, z, x - y) != a.noise4(x, y, z, x - y) ));
+
+	assert!(values.iter().all( |v| *v >= -1.0 && *v <= 1.0 ));
+	let mean = values.iter().sum::<f32>() / values.len() as f32;
+	assert!(mean.abs() < 0.1, "{}", mean);
+	assert!(values.iter().any( |v| *v > 0.3 ) && values.iter().any( |v| *v < -0.3 ));
+	assert_eq!(a.noise4(0.0, 0.0, 0.0, 0.0), 0.0); // zero at the lattice points
+	assert!((a.noise4(1.0, 2.0, 3.0, 4.0) - a.noise4(1.001, 2.0, 3.0, 4.0)).abs() < 0.01);
+}
+
+#[test]
+fn test_fractal_noise() {
+	let simplex = SimplexNoise::new(3);
+	let (x, y) = (0.3, 0.7);
+	assert_eq!(fbm(1, 2.0, 0.5, |f| simplex.noise4(x * f, y * f, 0.0, 0.0)), simplex.noise4(x, y, 0.0, 0.0));
+	assert_eq!(fbm(0, 2.0, 0.5, |f| simplex.noise4(x * f, y * f, 0.0, 0.0)), 0.0);
+
+	for &(x, y, z) in &sample_points() {
+		let noise = |f: f32| simplex.noise4(x * f, y * f, z * f, 1.0);
+		let value = fbm(6, 2.0, 0.5, noise);
+		assert!((-1.0..=1.0).contains(&value), "{}", value);
+		let value = ridged(6, 2.0, 0.5, noise);
+		assert!((0.0..=1.0).contains(&value), "{}", value);
+		let value = turbulence(6, 2.0, 0.5, noise);
+		assert!((0.0..=1.0).contains(&value), "{}", value);
+	}
+
+	let mut frequencies = Vec::new();
+	turbulence(4, 3.0, 0.5, |f| { frequencies.push(f); 0.0 });
+	assert_eq!(frequencies, vec![1.0, 3.0, 9.0, 27.0]);
+	assert_eq!(ridged(3, 2.0, 0.5, |_| 0.0), 1.0);
+	assert_eq!(turbulence(3, 2.0, 0.5, |_| -0.5), 0.5);
+}
+
+#[test]
+fn test_weighted_index() {
+	let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+	assert_eq!(weighted_index(&mut rng, &[]), None);
+	assert_eq!(weighted_index(&mut rng, &[0.0, -2.0]), None);
+	assert_eq!(weighted_index(&mut rng, &[0.0, 5.0, 0.0]), Some(1));
+
+	let mut counts = [0; 3];
+	for _ in 0..10_000 {
+		counts[weighted_index(&mut rng, &[0.5, 0.25, 0.25]).unwrap()] += 1;
+	}
+	assert!(counts[0] > 4700 && counts[0] < 5300, "{:?}", counts);
+	assert!(counts[2] > 2200 && counts[2] < 2800, "{:?}", counts);
+}